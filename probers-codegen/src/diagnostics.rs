@@ -0,0 +1,89 @@
+//! A small error-accumulator used while analyzing a provider trait, so that one malformed probe
+//! doesn't hide every other problem in the same trait.  Instead of bailing out the moment the first
+//! bad probe is found, callers push each problem they encounter into a `Diagnostics` and keep going;
+//! once analysis is done, `into_compile_error` renders everything collected as a single
+//! `TokenStream` of `compile_error!` invocations, each still pointing at its own span, so the user
+//! can fix every probe in one compile cycle instead of playing whack-a-mole.
+use crate::ProberError;
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+
+/// One collected problem: the primary message and its span, plus an optional "help" sub-note with
+/// its own span, e.g. pointing at the offending argument while the main message points at the whole
+/// probe method.
+type Entry = (Span, String, Option<(Span, String)>);
+
+#[derive(Debug, Default)]
+pub(crate) struct Diagnostics {
+    entries: Vec<Entry>,
+}
+
+impl Diagnostics {
+    pub(crate) fn new() -> Diagnostics {
+        Diagnostics {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Records a problem without stopping analysis; the caller should keep looking for more.
+    pub(crate) fn push<M: Into<String>>(&mut self, span: Span, message: M) {
+        self.entries.push((span, message.into(), None));
+    }
+
+    /// Like `push`, but with an additional "help" note of its own, shown as a second, separately
+    /// spanned diagnostic right after the main one, e.g. suggesting a fix.
+    pub(crate) fn push_with_help<M: Into<String>, H: Into<String>>(
+        &mut self,
+        span: Span,
+        message: M,
+        help_span: Span,
+        help: H,
+    ) {
+        self.entries
+            .push((span, message.into(), Some((help_span, help.into()))));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Renders every collected entry as one `TokenStream`, one `compile_error!` per diagnostic (plus
+    /// one more per help note), each keeping its own span.  Returns `None` if nothing was collected.
+    pub(crate) fn into_compile_error(self) -> Option<TokenStream> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let mut stream = TokenStream::new();
+        for (span, message, help) in self.entries {
+            stream.extend(quote_spanned! {span=> compile_error! { #message } });
+
+            if let Some((help_span, help_message)) = help {
+                let help_message = format!("help: {}", help_message);
+                stream.extend(quote_spanned! {help_span=> compile_error! { #help_message } });
+            }
+        }
+
+        Some(stream)
+    }
+
+    /// Collapses every collected entry into a single `ProberError`, for call sites that only have
+    /// room to report one error (e.g. `find_providers`, which silently discards invalid traits and
+    /// so never shows these messages to the user anyway).  Uses the first entry's span and
+    /// concatenates every message, in order, into one string.
+    pub(crate) fn into_prober_error(self) -> ProberError {
+        let span = self
+            .entries
+            .first()
+            .map(|(span, _, _)| *span)
+            .unwrap_or_else(Span::call_site);
+        let message = self
+            .entries
+            .into_iter()
+            .map(|(_, message, _)| message)
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        ProberError::new(message, span)
+    }
+}
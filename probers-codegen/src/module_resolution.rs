@@ -0,0 +1,252 @@
+//! `find_providers` in `provider.rs` only sees the `#[prober]` traits physically present in one
+//! already-parsed `syn::File`, but most real crates split their provider traits across several
+//! files using `mod foo;` declarations.  This module walks a crate's module tree starting from its
+//! root source file, following `mod foo;` / `mod foo { ... }` declarations to the files (or inline
+//! item lists) that back them, and aggregates every provider discovered anywhere in the tree along
+//! with the module path it was found in.
+use crate::provider::{find_providers, ProviderSpecification};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A provider trait discovered somewhere in a crate's module tree, together with the sequence of
+/// `mod` names leading to the file it was declared in, e.g. `["net", "probes"]`.
+pub(crate) struct DiscoveredProvider {
+    pub(crate) module_path: Vec<String>,
+    pub(crate) spec: ProviderSpecification,
+}
+
+impl DiscoveredProvider {
+    /// The module path and provider name joined with `::`, e.g. `net::probes::net_probes`.  Later
+    /// codegen can use this to disambiguate identically-named traits declared in different modules,
+    /// the way `duplicate provider name` detection needs to.
+    pub(crate) fn qualified_name(&self) -> String {
+        let mut parts = self.module_path.clone();
+        parts.push(self.spec.name().to_string());
+        parts.join("::")
+    }
+}
+
+/// Parses `root` and every file it transitively reaches via `mod foo;` / `mod foo { ... }`
+/// declarations, and returns every `#[prober]` provider trait found anywhere in the tree.
+pub(crate) fn discover_providers(root: &Path) -> std::io::Result<Vec<DiscoveredProvider>> {
+    let root_dir = root.parent().unwrap_or_else(|| Path::new("."));
+    let source = fs::read_to_string(root)?;
+    let file = syn::parse_file(&source).map_err(to_io_error)?;
+
+    let mut providers = Vec::new();
+    let mut module_path = Vec::new();
+    visit_items(&file.items, root_dir, &mut module_path, &mut providers)?;
+    Ok(providers)
+}
+
+fn visit_items(
+    items: &[syn::Item],
+    dir: &Path,
+    module_path: &mut Vec<String>,
+    providers: &mut Vec<DiscoveredProvider>,
+) -> std::io::Result<()> {
+    // Every provider trait physically present in this item list (but *not* nested inside an inline
+    // `mod foo { ... }`) belongs to the current module path.  `find_providers` wants a whole
+    // `syn::File`, so wrap the items in one rather than duplicating its visitor logic here -- but
+    // its `Visit` impl only overrides `visit_item_trait`, so `syn::visit`'s default traversal
+    // already walks into inline module bodies on its own.  The loop below also recurses into those
+    // same inline modules, with the correct `module_path` this time, so inline mods are excluded
+    // here to avoid discovering their providers twice (once here with a wrong, truncated path).
+    let top_level_items: Vec<syn::Item> = items
+        .iter()
+        .filter(|item| !matches!(item, syn::Item::Mod(item_mod) if item_mod.content.is_some()))
+        .cloned()
+        .collect();
+    let wrapper = syn::File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: top_level_items,
+    };
+    for spec in find_providers(&wrapper) {
+        providers.push(DiscoveredProvider {
+            module_path: module_path.clone(),
+            spec,
+        });
+    }
+
+    for item in items {
+        if let syn::Item::Mod(item_mod) = item {
+            module_path.push(item_mod.ident.to_string());
+
+            match &item_mod.content {
+                // `mod foo { ... }`: the module's items are right here, in the same file, so just
+                // recurse into them directly without touching the filesystem.
+                Some((_, inline_items)) => {
+                    visit_items(inline_items, dir, module_path, providers)?;
+                }
+                // `mod foo;`: resolve the backing file using the standard lookup rules and recurse
+                // into it, relative to its own directory (so a nested `mod bar;` inside it resolves
+                // relative to where it actually lives, not where its parent lives).
+                None => {
+                    if let Some(path) = resolve_mod_file(dir, &item_mod.ident.to_string(), &item_mod.attrs)? {
+                        let source = fs::read_to_string(&path)?;
+                        let child_file = syn::parse_file(&source).map_err(to_io_error)?;
+                        let child_dir = path.parent().unwrap_or(dir);
+                        visit_items(&child_file.items, child_dir, module_path, providers)?;
+                    }
+                    // If the file can't be found, this mirrors `find_providers`'s existing
+                    // behavior of silently ignoring anything it can't make sense of -- a genuine
+                    // mistake here is still a compile error on its own, just not one this
+                    // discovery pass needs to diagnose itself.
+                }
+            }
+
+            module_path.pop();
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the file backing an out-of-line `mod foo;` declaration, following rustc's own lookup
+/// rules: an explicit `#[path = "..."]` attribute wins if present; otherwise `foo.rs` is tried, then
+/// `foo/mod.rs`. Returns `None` if neither exists.
+fn resolve_mod_file(
+    dir: &Path,
+    name: &str,
+    attrs: &[syn::Attribute],
+) -> std::io::Result<Option<PathBuf>> {
+    if let Some(explicit) = explicit_path_attr(attrs) {
+        let candidate = dir.join(explicit);
+        return Ok(if candidate.is_file() {
+            Some(candidate)
+        } else {
+            None
+        });
+    }
+
+    let flat = dir.join(format!("{}.rs", name));
+    if flat.is_file() {
+        return Ok(Some(flat));
+    }
+
+    let nested = dir.join(name).join("mod.rs");
+    if nested.is_file() {
+        return Ok(Some(nested));
+    }
+
+    Ok(None)
+}
+
+/// Looks for a `#[path = "..."]` attribute among `attrs` and returns its string value, if present.
+fn explicit_path_attr(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        if !attr.path.is_ident("path") {
+            return None;
+        }
+        match attr.parse_meta().ok()? {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        }
+    })
+}
+
+fn to_io_error(e: syn::Error) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Writes a small module tree under a fresh temp directory: `root_source` becomes the crate
+    /// root, and each `(relative_path, contents)` pair in `files` becomes an additional file
+    /// alongside it. Returns the path to the root file.
+    ///
+    /// `name` should be unique per call site (e.g. the test function's name) -- tests in this
+    /// module run concurrently in the same process, and `std::process::id()` alone is the same
+    /// for all of them, which let two tests race on the same directory and stomp each other's
+    /// fixture files.
+    fn write_tree(name: &str, root_source: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "probers_codegen_module_resolution_test_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let root = dir.join("lib.rs");
+        fs::write(&root, root_source).unwrap();
+
+        for (relative, contents) in files {
+            let path = dir.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).unwrap();
+            }
+            fs::write(path, contents).unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn discovers_providers_across_mod_declarations() {
+        let root = write_tree(
+            "discovers_providers_across_mod_declarations",
+            r#"
+                mod net;
+                mod db {
+                    #[prober]
+                    trait DbProbes {
+                        fn query_started(query: &str);
+                    }
+                }
+            "#,
+            &[(
+                "net.rs",
+                r#"
+                    #[prober]
+                    trait NetProbes {
+                        fn connection_opened(addr: &str);
+                    }
+                "#,
+            )],
+        );
+
+        let providers = discover_providers(&root).unwrap();
+        let qualified_names: Vec<String> = providers.iter().map(|p| p.qualified_name()).collect();
+
+        // Asserting the exact count, not just `.contains`, catches a provider declared inside an
+        // inline `mod { ... }` being discovered twice (once with a wrong, truncated module path).
+        assert_eq!(2, providers.len());
+        assert!(qualified_names.contains(&"net::net_probes".to_string()));
+        assert!(qualified_names.contains(&"db::db_probes".to_string()));
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    fn honors_explicit_path_attribute() {
+        let root = write_tree(
+            "honors_explicit_path_attribute",
+            r#"
+                #[path = "custom_location.rs"]
+                mod net;
+            "#,
+            &[(
+                "custom_location.rs",
+                r#"
+                    #[prober]
+                    trait NetProbes {
+                        fn connection_opened(addr: &str);
+                    }
+                "#,
+            )],
+        );
+
+        let providers = discover_providers(&root).unwrap();
+        assert_eq!(1, providers.len());
+        assert_eq!("net::net_probes", providers[0].qualified_name());
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+}
@@ -3,6 +3,7 @@
 //! macros and nothing else.  That's an inconvenient restriction, especially since there's quite a
 //! lot of overlap between the macro code and the build-time probe code generation logic.  Hence,
 //! this bifurcation.
+use crate::diagnostics::Diagnostics;
 use crate::probe::ProbeSpecification;
 use crate::provider::{find_probes, get_provider_name};
 use crate::{ProberError, ProberResult};
@@ -110,8 +111,11 @@ pub fn init_provider_impl(typ: syn::TypePath) -> ProberResult<TokenStream> {
 /// Actual implementation of the macro logic, factored out of the proc macro itself so that it's
 /// more testable
 pub fn prober_impl(item: ItemTrait) -> ProberResult<TokenStream> {
-    // Look at the methods on the trait and translate each one into a probe specification
-    let probes = find_probes(&item)?;
+    // Look at the methods on the trait and translate each one into a probe specification.  Errors
+    // are collected here instead of reporting just the first one, so this collapses them into a
+    // single `ProberError` the same way `ProviderSpecification::from_trait` does; callers which
+    // want every individual diagnostic reported separately should use `find_probes` directly.
+    let probes = find_probes(&item).map_err(Diagnostics::into_prober_error)?;
 
     // Re-generate this trait as a struct with our probing implementation in it
     let probe_struct = generate_prober_struct(&item, &probes)?;
@@ -452,4 +456,101 @@ fn get_provider_instance_var_name(trait_name: &Ident) -> Ident {
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+    use crate::testdata::*;
+    use syn::parse_quote;
+
+    fn get_filtered_test_traits(with_errors: bool) -> Vec<TestProviderTrait> {
+        get_test_provider_traits(|t: &TestProviderTrait| t.expected_error.is_some() == with_errors)
+    }
+
+    #[test]
+    fn prober_impl_fails_with_invalid_traits() {
+        for test_trait in get_filtered_test_traits(true) {
+            let trait_decl = test_trait.tokenstream;
+            let item_trait: ItemTrait = parse_quote! {
+                #[prober]
+                #trait_decl
+            };
+
+            let error = prober_impl(item_trait).err();
+            assert_ne!(
+                None, error,
+                "The invalid trait '{}' was accepted by prober_impl",
+                test_trait.description
+            );
+
+            let expected_error_substring = test_trait.expected_error.unwrap();
+            let message = error.unwrap().message;
+            assert!(
+                message.contains(expected_error_substring),
+                "The invalid trait '{}' should produce an error containing '{}' but instead it produced '{}'",
+                test_trait.description,
+                expected_error_substring,
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn prober_impl_expands_valid_traits() {
+        for test_trait in get_filtered_test_traits(false) {
+            let trait_decl = test_trait.tokenstream;
+            let item_trait: ItemTrait = parse_quote! {
+                #[prober]
+                #trait_decl
+            };
+            let ident = item_trait.ident.clone();
+            let probe_names: Vec<Ident> = item_trait
+                .items
+                .iter()
+                .filter_map(|i| match i {
+                    syn::TraitItem::Method(m) => Some(m.sig.ident.clone()),
+                    _ => None,
+                })
+                .collect();
+
+            let tokens = prober_impl(item_trait).unwrap_or_else(|err| {
+                panic!(
+                    "The valid trait '{}' failed to expand: {}",
+                    test_trait.description, err.message
+                )
+            });
+
+            // Make sure what came out the other end is itself valid Rust, then spot-check for the
+            // handful of items every expansion must contain: the struct standing in for the trait,
+            // a `get_<probe>_probe` accessor per probe, and the `OnceCell`-backed impl module.
+            let file: syn::File =
+                syn::parse2(quote! { #tokens }).expect("prober_impl output must parse as Rust");
+            let rendered = quote! { #file }.to_string();
+
+            assert!(
+                rendered.contains(&format!("struct {}", ident)),
+                "expansion of '{}' is missing its `struct {}`",
+                test_trait.description,
+                ident
+            );
+            assert!(
+                rendered.contains("__try_init_provider"),
+                "expansion of '{}' is missing `__try_init_provider`",
+                test_trait.description
+            );
+            assert!(
+                rendered.contains("OnceCell"),
+                "expansion of '{}' is missing its `OnceCell` statics",
+                test_trait.description
+            );
+
+            for probe_name in probe_names {
+                let accessor = format!("get_{}_probe", probe_name);
+                assert!(
+                    rendered.contains(&accessor),
+                    "expansion of '{}' is missing `{}`",
+                    test_trait.description,
+                    accessor
+                );
+            }
+        }
+    }
+}
@@ -2,15 +2,17 @@
 //! `probe-rs` provider traits therein, as well as analyze those traits and produce `ProbeSpec`s for
 //! each of the probes they contain.  Once the provider traits have been discovered, other modules
 //! in this crate can then process them in various ways
+use crate::diagnostics::Diagnostics;
 use crate::probe::ProbeSpecification;
 use heck::SnakeCase;
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::path::PathBuf;
 use syn::spanned::Spanned;
 use syn::visit::Visit;
-use syn::{ItemTrait, TraitItem};
+use syn::{FnArg, Ident, ItemTrait, TraitItem};
 
 use crate::{ProberError, ProberResult};
 
@@ -20,6 +22,12 @@ pub(crate) struct ProviderSpecification {
     item_trait: ItemTrait,
     token_stream: TokenStream,
     probes: Vec<ProbeSpecification>,
+    /// The `key = value` pairs from the `#[prober(...)]` attribute, other than `provider` (which is
+    /// consumed to produce `name` instead).  Free-form, so later codegen can define new keys -- e.g.
+    /// `stability`, `version` -- without this struct needing to know about each one.
+    metadata: Vec<(String, syn::Lit)>,
+    /// The trait's own `///` doc comment, if any, harvested for `generate_documentation_markdown`.
+    doc: Option<String>,
 }
 
 impl fmt::Debug for ProviderSpecification {
@@ -42,22 +50,29 @@ impl fmt::Debug for ProviderSpecification {
 
 impl ProviderSpecification {
     pub(crate) fn from_trait(item_trait: &ItemTrait) -> ProberResult<ProviderSpecification> {
-        let probes = find_probes(item_trait)?;
+        let probes = find_probes(item_trait).map_err(Diagnostics::into_prober_error)?;
+        let (name_override, metadata) = parse_prober_attribute(item_trait)?;
         let token_stream = quote! { #item_trait };
         let hash = crate::hashing::hash_token_stream(&token_stream);
         // The provider name must be chosen carefully.  As of this writing (2019-04) the `bpftrace`
         // and `bcc` tools have, shall we say, "evolving" support for USDT.  As of now, with the
         // latest git version of `bpftrace`, the provider name can't have dots or colons.  For now,
-        // then, the provider name is just the name of the provider trait, converted into
-        // snake_case for consistency with USDT naming conventions.  If two modules in the same
-        // process have the same provider name, they will conflict and some unspecified `bad
+        // then, the provider name defaults to the name of the provider trait, converted into
+        // snake_case for consistency with USDT naming conventions -- unless the user pins one
+        // explicitly with `#[prober(provider = "...")]`, which is the recommended way to avoid the
+        // cross-module name-collision hazard: two modules in the same process whose trait idents
+        // happen to snake_case to the same string will otherwise conflict and some unspecified `bad
         // things` will happen.
+        let name = name_override.unwrap_or_else(|| item_trait.ident.to_string().to_snake_case());
+        let doc = extract_doc_comment(&item_trait.attrs);
         Ok(ProviderSpecification {
-            name: item_trait.ident.to_string().to_snake_case(),
+            name,
             hash,
             item_trait: item_trait.clone(),
             token_stream,
             probes,
+            metadata,
+            doc,
         })
     }
 
@@ -73,6 +88,12 @@ impl ProviderSpecification {
         PathBuf::from(format!("{}.a", self.name_with_hash()))
     }
 
+    /// The path of the generated Markdown documentation artifact that `generate_documentation_markdown`
+    /// produces for this provider, meant to sit alongside the native provider source/lib.
+    pub(crate) fn native_provider_doc_filename(&self) -> PathBuf {
+        PathBuf::from(format!("{}.md", self.name_with_hash()))
+    }
+
     pub(crate) fn name(&self) -> &str {
         &self.name
     }
@@ -84,20 +105,38 @@ impl ProviderSpecification {
     pub(crate) fn probes(&self) -> &Vec<ProbeSpecification> {
         &self.probes
     }
+
+    /// The `key = value` pairs from this provider's `#[prober(...)]` attribute, other than
+    /// `provider` itself (which is folded into `name`).
+    pub(crate) fn metadata(&self) -> &[(String, syn::Lit)] {
+        &self.metadata
+    }
+
+    /// The trait's own `///` doc comment, if it has one.
+    pub(crate) fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
 }
 
 /// Scans the AST of a Rust source file, finding all traits marked with the `prober` attribute,
 /// parses the contents of the trait, and deduces the provider spec from that.
 ///
+/// A provider trait may declare supertraits, e.g. `trait AppProbes: NetProbes + DbProbes`, in which
+/// case the composed provider's probes are those of `AppProbes` itself plus every probe declared on
+/// `NetProbes` and `DbProbes` -- which is why candidates are resolved in dependency order: a trait
+/// is composed only once every one of its supertraits has itself been composed, so supertraits can
+/// appear anywhere in the file relative to the trait that extends them, and probes are inherited
+/// transitively through a chain of supertraits.
+///
 /// Note that if any traits are encountered with the `prober` attribute but which are in some way
-/// invalid as providers, those traits will be silently ignored.  At compile time the `prober`
-/// attribute will cause a very detailed compile error so there's no chance the user will miss this
-/// mistake.
+/// invalid as providers -- including a provider whose supertrait composition fails -- those traits
+/// will be silently ignored.  At compile time the `prober` attribute will cause a very detailed
+/// compile error so there's no chance the user will miss this mistake.
 pub(crate) fn find_providers(ast: &syn::File) -> Vec<ProviderSpecification> {
     //Construct an implementation of the `syn` crate's `Visit` trait which will examine all trait
     //declarations in the file looking for possible providers
     struct Visitor {
-        providers: Vec<ProviderSpecification>,
+        candidate_traits: Vec<ItemTrait>,
     }
 
     impl<'ast> Visit<'ast> for Visitor {
@@ -114,51 +153,467 @@ pub(crate) fn find_providers(ast: &syn::File) -> Vec<ProviderSpecification> {
                 })
             {
                 //This looks like a provider trait
-                if let Ok(provider) = ProviderSpecification::from_trait(i) {
-                    self.providers.push(provider)
-                }
+                self.candidate_traits.push(i.clone());
             }
         }
     }
 
     let mut visitor = Visitor {
-        providers: Vec::new(),
+        candidate_traits: Vec::new(),
     };
     visitor.visit_file(ast);
 
-    visitor.providers
+    // Resolve every candidate into its final, fully-composed `ProviderSpecification`.  A trait
+    // with supertraits is only composed once every one of its supertraits is itself already
+    // resolved, so a multi-level chain -- e.g. `GrandProbes: AppProbes` where `AppProbes: NetProbes
+    // + DbProbes` -- inherits probes transitively through `AppProbes`'s own *composed* spec,
+    // instead of only the one level a single fixed pass against the candidates' bare,
+    // supertrait-less specs would give it.
+    let mut resolved: Vec<ProviderSpecification> = Vec::new();
+    let mut pending: Vec<&ItemTrait> = visitor.candidate_traits.iter().collect();
+
+    while !pending.is_empty() {
+        let (ready, not_ready): (Vec<&ItemTrait>, Vec<&ItemTrait>) =
+            pending.into_iter().partition(|t| {
+                supertrait_idents(t)
+                    .iter()
+                    .all(|(_, ident)| resolved.iter().any(|p| &p.item_trait.ident == ident))
+            });
+
+        if ready.is_empty() {
+            // Nothing left in `not_ready` can ever become ready: each is missing a supertrait
+            // that's not itself a known provider, or is part of a dependency cycle.  Resolve them
+            // one last time against whatever's already composed, so `from_trait_with_supertraits`
+            // reports why, then stop instead of looping forever.
+            for t in not_ready {
+                if let Ok(spec) = resolve_candidate(t, &resolved) {
+                    resolved.push(spec);
+                }
+            }
+            break;
+        }
+
+        for t in ready {
+            if let Ok(spec) = resolve_candidate(t, &resolved) {
+                resolved.push(spec);
+            }
+        }
+
+        pending = not_ready;
+    }
+
+    resolved
+}
+
+/// Composes a single candidate trait into a `ProviderSpecification`, resolving its supertraits (if
+/// any) against `known_providers`. `known_providers` must already contain the *composed* spec for
+/// each of `item_trait`'s direct supertraits -- see the fixpoint loop in `find_providers`.
+fn resolve_candidate(
+    item_trait: &ItemTrait,
+    known_providers: &[ProviderSpecification],
+) -> ProberResult<ProviderSpecification> {
+    if item_trait.supertraits.is_empty() {
+        ProviderSpecification::from_trait(item_trait)
+    } else {
+        from_trait_with_supertraits(item_trait, known_providers)
+    }
+}
+
+/// Like `ProviderSpecification::from_trait`, but also resolves `item_trait`'s supertrait bounds
+/// (e.g. `trait AppProbes: NetProbes + DbProbes`) against `known_providers`, merging each
+/// supertrait's probes -- including those it in turn inherits -- into the result. Fails with a
+/// spanned `ProberError` if a supertrait isn't itself found among `known_providers` (i.e. isn't a
+/// `#[prober]` provider), or if merging produces two probes with the same name. The composed
+/// provider's hash incorporates the token stream of every trait it inherits from, directly or
+/// transitively, so a change to an inherited probe invalidates the generated native library just as
+/// a change to one of this trait's own probes would.
+fn from_trait_with_supertraits(
+    item_trait: &ItemTrait,
+    known_providers: &[ProviderSpecification],
+) -> ProberResult<ProviderSpecification> {
+    let mut spec = ProviderSpecification::from_trait(item_trait)?;
+
+    let mut seen_probe_names: HashSet<String> = item_trait
+        .items
+        .iter()
+        .filter_map(|i| match i {
+            TraitItem::Method(m) => Some(m.sig.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let mut combined_tokens = spec.token_stream.clone();
+
+    for (bound_span, supertrait_ident) in supertrait_idents(item_trait) {
+        let supertrait_provider = known_providers
+            .iter()
+            .find(|p| p.item_trait.ident == supertrait_ident)
+            .ok_or_else(|| {
+                ProberError::new(
+                    format!(
+                        "supertrait '{}' is not a #[prober] provider; every supertrait of a provider trait must itself be one",
+                        supertrait_ident
+                    ),
+                    bound_span,
+                )
+            })?;
+
+        // A composed provider's `token_stream` holds its own trait followed by every trait it in
+        // turn inherits from (see the end of this function), so this covers the whole chain.
+        let inherited: syn::File = syn::parse2(supertrait_provider.token_stream.clone())
+            .map_err(|e| ProberError::new(e.to_string(), bound_span))?;
+        let inherited_traits: Vec<&ItemTrait> = inherited
+            .items
+            .iter()
+            .filter_map(|i| match i {
+                syn::Item::Trait(t) => Some(t),
+                _ => None,
+            })
+            .collect();
+
+        for item in inherited_traits.iter().flat_map(|t| t.items.iter()) {
+            if let TraitItem::Method(m) = item {
+                let name = m.sig.ident.to_string();
+                if !seen_probe_names.insert(name.clone()) {
+                    return Err(ProberError::new(
+                        format!(
+                            "probe '{}' inherited from supertrait '{}' collides with another probe of the same name",
+                            name, supertrait_ident
+                        ),
+                        m.sig.ident.span(),
+                    ));
+                }
+            }
+        }
+
+        // Re-derive the inherited probes from their trait declarations, rather than copying
+        // `supertrait_provider.probes` wholesale, since that keeps this independent of whether
+        // `ProbeSpecification` itself is `Clone`.
+        for inherited_trait in inherited_traits {
+            let inherited_probes =
+                find_probes(inherited_trait).map_err(Diagnostics::into_prober_error)?;
+            spec.probes.extend(inherited_probes);
+        }
+
+        combined_tokens.extend(supertrait_provider.token_stream.clone());
+    }
+
+    spec.hash = crate::hashing::hash_token_stream(&combined_tokens);
+    spec.token_stream = combined_tokens;
+
+    Ok(spec)
+}
+
+/// Extracts the name (and the span of the bound that named it) of every trait-typed supertrait
+/// bound on `item_trait`, e.g. `[(span_of_NetProbes, NetProbes), (span_of_DbProbes, DbProbes)]` for
+/// `trait AppProbes: NetProbes + DbProbes`. Lifetime bounds, which a supertrait list may also carry,
+/// are not providers and so are skipped.
+fn supertrait_idents(item_trait: &ItemTrait) -> Vec<(proc_macro2::Span, Ident)> {
+    item_trait
+        .supertraits
+        .iter()
+        .filter_map(|bound| match bound {
+            syn::TypeParamBound::Trait(trait_bound) => trait_bound
+                .path
+                .segments
+                .last()
+                .map(|segment| (trait_bound.path.span(), segment.ident.clone())),
+            syn::TypeParamBound::Lifetime(_) => None,
+        })
+        .collect()
+}
+
+/// Checks `providers` for two or more providers that would claim the same USDT provider name --
+/// e.g. because two trait idents happen to snake_case to the same string, or two
+/// `#[prober(provider = "...")]` overrides collide -- and, within each individual provider, for two
+/// probes with the same name. This is the check for the hazard the comment in `from_trait` warns
+/// about: without it, a colliding provider name produces "some unspecified bad things" at the
+/// native tracing layer instead of a readable compile error.
+///
+/// Unlike a simple `Result`, this keeps looking even after the first collision is found: every
+/// colliding provider and every colliding probe is recorded in the returned `Diagnostics`, the same
+/// way `find_probes` already collects every malformed probe instead of stopping at the first one, so
+/// the user sees every conflict in one compile cycle.
+pub(crate) fn check_for_duplicate_names(providers: &[ProviderSpecification]) -> Result<(), Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+
+    // Track the first provider seen for each name, so later occurrences -- presumably the actual
+    // mistakes, since the first one is whichever the user wrote first -- get flagged against it.
+    let mut seen_provider_names: HashMap<&str, &ProviderSpecification> = HashMap::new();
+    for provider in providers {
+        match seen_provider_names.get(provider.name.as_str()) {
+            Some(first) => {
+                diagnostics.push(
+                    provider.item_trait.span(),
+                    format!(
+                        "provider name '{}' is also used by trait '{}'; provider names must be unique within a process",
+                        provider.name, first.item_trait.ident,
+                    ),
+                );
+            }
+            None => {
+                seen_provider_names.insert(&provider.name, provider);
+            }
+        }
+
+        check_for_duplicate_probe_names(provider, &mut diagnostics);
+    }
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(diagnostics)
+    }
+}
+
+/// Checks a single provider's trait methods for two probes sharing the same name, pushing one
+/// diagnostic per repeat occurrence into `diagnostics`.
+fn check_for_duplicate_probe_names(provider: &ProviderSpecification, diagnostics: &mut Diagnostics) {
+    let mut seen_probe_names: HashSet<String> = HashSet::new();
+    for item in provider.item_trait.items.iter() {
+        if let TraitItem::Method(m) = item {
+            let name = m.sig.ident.to_string();
+            if !seen_probe_names.insert(name.clone()) {
+                diagnostics.push(
+                    m.sig.ident.span(),
+                    format!(
+                        "provider '{}' declares more than one probe named '{}'",
+                        provider.name, name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Extracts the text of a `///` (or `#[doc = "..."]`) doc comment from `attrs`, joining a
+/// multi-line comment's individual `#[doc = "..."]` attributes -- one per source line -- into a
+/// single `\n`-separated string. Returns `None` if `attrs` carries no doc comment at all.
+fn extract_doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| {
+            if !attr.path.is_ident("doc") {
+                return None;
+            }
+            match attr.parse_meta().ok()? {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) => Some(s.value().trim().to_string()),
+                _ => None,
+            }
+        })
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Renders a Markdown document listing every probe `provider` declares: its name, the
+/// fully-qualified USDT name a tracing tool would see it under, its argument signature, and
+/// whatever `///` doc comment prose was attached to it. A probe with no doc comment gets an
+/// `_undocumented_` note instead of a blank section, so a missing comment is visible in the
+/// rendered artifact as well as via `undocumented_probe_names` below.
+pub(crate) fn generate_documentation_markdown(provider: &ProviderSpecification) -> String {
+    let mut doc = format!("# {}\n\n", provider.name);
+
+    if let Some(trait_doc) = &provider.doc {
+        doc.push_str(trait_doc);
+        doc.push_str("\n\n");
+    }
+
+    for item in provider.item_trait.items.iter() {
+        if let TraitItem::Method(m) = item {
+            let probe_name = m.sig.ident.to_string();
+            let usdt_name = format!("{}:{}", provider.name, probe_name);
+            let arg_types: Vec<String> = m
+                .sig
+                .inputs
+                .iter()
+                .filter_map(|arg| match arg {
+                    FnArg::Typed(pat_type) => {
+                        let ty = &pat_type.ty;
+                        Some(quote! { #ty }.to_string())
+                    }
+                    FnArg::Receiver(_) => None,
+                })
+                .collect();
+
+            doc.push_str(&format!("## `{}`\n\n", probe_name));
+            doc.push_str(&format!("USDT name: `{}`\n\n", usdt_name));
+            doc.push_str(&format!("Arguments: `({})`\n\n", arg_types.join(", ")));
+            match extract_doc_comment(&m.attrs) {
+                Some(probe_doc) => doc.push_str(&format!("{}\n\n", probe_doc)),
+                None => doc.push_str("_undocumented_\n\n"),
+            }
+        }
+    }
+
+    doc
+}
+
+/// Returns the name of every probe method on `item` with no `///` doc comment, for a "doc
+/// coverage" pass that warns about undocumented probes the way a documentation-coverage lint would.
+pub(crate) fn undocumented_probe_names(item: &ItemTrait) -> Vec<String> {
+    item.items
+        .iter()
+        .filter_map(|i| match i {
+            TraitItem::Method(m) if extract_doc_comment(&m.attrs).is_none() => {
+                Some(m.sig.ident.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parses the arguments to a trait's `#[prober(...)]` attribute, if it has any, into an optional
+/// provider name override (the `provider = "..."` entry) and the remaining `key = value` pairs,
+/// which are carried along on `ProviderSpecification` as free-form metadata for later codegen to
+/// consume (e.g. a `stability` or `version` annotation).  A bare `#[prober]` with no parenthesized
+/// arguments is equivalent to one with none of these keys set.
+fn parse_prober_attribute(
+    item_trait: &ItemTrait,
+) -> ProberResult<(Option<String>, Vec<(String, syn::Lit)>)> {
+    let attr = match item_trait.attrs.iter().find(|attr| {
+        attr.path
+            .segments
+            .iter()
+            .last()
+            .map(|s| s.ident == "prober")
+            .unwrap_or(false)
+    }) {
+        Some(attr) => attr,
+        None => return Ok((None, Vec::new())),
+    };
+
+    let list = match attr.parse_meta() {
+        Ok(syn::Meta::Path(_)) => return Ok((None, Vec::new())),
+        Ok(syn::Meta::List(list)) => list,
+        Ok(syn::Meta::NameValue(nv)) => {
+            return Err(ProberError::new(
+                "`#[prober(...)]` takes a parenthesized list of `key = value` pairs, not `prober = ...`",
+                nv.span(),
+            ));
+        }
+        Err(e) => {
+            return Err(ProberError::new(
+                format!("Invalid `#[prober(...)]` attribute: {}", e),
+                attr.span(),
+            ));
+        }
+    };
+
+    let mut name_override: Option<String> = None;
+    let mut metadata: Vec<(String, syn::Lit)> = Vec::new();
+
+    for nested in list.nested.iter() {
+        let nv = match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => nv,
+            other => {
+                return Err(ProberError::new(
+                    "expected a `key = value` pair, e.g. `provider = \"my_app\"`",
+                    other.span(),
+                ));
+            }
+        };
+
+        let key = match nv.path.get_ident() {
+            Some(ident) => ident.to_string(),
+            None => {
+                return Err(ProberError::new(
+                    "expected a simple identifier as the key",
+                    nv.path.span(),
+                ));
+            }
+        };
+
+        match key.as_str() {
+            "provider" => match &nv.lit {
+                syn::Lit::Str(s) => name_override = Some(s.value()),
+                other => {
+                    return Err(ProberError::new(
+                        "`provider` must be a string literal",
+                        other.span(),
+                    ));
+                }
+            },
+            "stability" => match &nv.lit {
+                syn::Lit::Str(_) => metadata.push((key, nv.lit.clone())),
+                other => {
+                    return Err(ProberError::new(
+                        "`stability` must be a string literal",
+                        other.span(),
+                    ));
+                }
+            },
+            "version" => match &nv.lit {
+                syn::Lit::Int(_) => metadata.push((key, nv.lit.clone())),
+                other => {
+                    return Err(ProberError::new("`version` must be an integer", other.span()));
+                }
+            },
+            _ => {
+                return Err(ProberError::new(
+                    format!(
+                        "unknown `#[prober(...)]` key `{}`; expected one of `provider`, `stability`, `version`",
+                        key
+                    ),
+                    nv.path.span(),
+                ));
+            }
+        }
+    }
+
+    Ok((name_override, metadata))
 }
 
 /// Looking at the methods defined on the trait, deduce from those methods the probes that we will
 /// need to define, including their arg counts and arg types.
 ///
-/// If the trait contains anything other than method declarations, or any of the declarations are
-/// not suitable as probes, an error is returned
-fn find_probes(item: &ItemTrait) -> ProberResult<Vec<ProbeSpecification>> {
+/// Unlike a simple `Result`, this keeps looking even after the first problem is found: every
+/// malformed probe and every non-method trait item is recorded in the returned `Diagnostics` so the
+/// caller can show the user every problem in one compile cycle, rather than just the first one.
+fn find_probes(item: &ItemTrait) -> Result<Vec<ProbeSpecification>, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+
     if item.generics.type_params().next() != None || item.generics.lifetimes().next() != None {
-        return Err(ProberError::new(
-            "Probe traits must not take any lifetime or type parameters",
+        diagnostics.push(
             item.span(),
-        ));
+            "Probe traits must not take any lifetime or type parameters",
+        );
     }
 
     // Look at the methods on the trait and translate each one into a probe specification
     let mut specs: Vec<ProbeSpecification> = Vec::new();
     for f in item.items.iter() {
         match f {
-            TraitItem::Method(ref m) => {
-                specs.push(ProbeSpecification::from_method(item, m)?);
-            }
+            TraitItem::Method(ref m) => match ProbeSpecification::from_method(item, m) {
+                Ok(spec) => specs.push(spec),
+                Err(e) => diagnostics.push_with_help(
+                    e.span,
+                    e.message,
+                    m.sig.ident.span(),
+                    "probe arguments must be `Copy` or references; consider `&str` instead of `String`",
+                ),
+            },
             _ => {
-                return Err(ProberError::new(
-                    "Probe traits must consist entirely of methods, no other contents",
+                diagnostics.push(
                     f.span(),
-                ));
+                    "Probe traits must consist entirely of methods, no other contents",
+                );
             }
         }
     }
 
-    Ok(specs)
+    if diagnostics.is_empty() {
+        Ok(specs)
+    } else {
+        Err(diagnostics)
+    }
 }
 
 #[cfg(test)]
@@ -268,4 +723,280 @@ mod test {
             assert_eq!(probes, test_trait.probes.unwrap_or(Vec::new()));
         }
     }
+
+    #[test]
+    fn prober_attribute_overrides_provider_name() {
+        let item_trait: ItemTrait = parse_quote! {
+            #[prober(provider = "custom.provider.name")]
+            trait CustomNamedProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+
+        let spec = ProviderSpecification::from_trait(&item_trait).unwrap();
+        assert_eq!("custom.provider.name", spec.name());
+    }
+
+    #[test]
+    fn prober_attribute_without_provider_key_falls_back_to_snake_case_ident() {
+        let item_trait: ItemTrait = parse_quote! {
+            #[prober(stability = "stable", version = 2)]
+            trait StableProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+
+        let spec = ProviderSpecification::from_trait(&item_trait).unwrap();
+        assert_eq!("stable_probes", spec.name());
+        assert_eq!(2, spec.metadata().len());
+    }
+
+    #[test]
+    fn prober_attribute_rejects_unknown_key() {
+        let item_trait: ItemTrait = parse_quote! {
+            #[prober(bogus = "nope")]
+            trait BogusProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+
+        let error = ProviderSpecification::from_trait(&item_trait).err();
+        assert_ne!(None, error);
+        assert!(error.unwrap().message.contains("unknown"));
+    }
+
+    #[test]
+    fn prober_attribute_rejects_wrong_value_type() {
+        let item_trait: ItemTrait = parse_quote! {
+            #[prober(version = "not a number")]
+            trait BadVersionProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+
+        let error = ProviderSpecification::from_trait(&item_trait).err();
+        assert_ne!(None, error);
+        assert!(error.unwrap().message.contains("version"));
+    }
+
+    #[test]
+    fn detects_duplicate_provider_names() {
+        let trait_a: ItemTrait = parse_quote! {
+            trait NetProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+        let trait_b: ItemTrait = parse_quote! {
+            #[prober(provider = "net_probes")]
+            trait OtherNetProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+
+        let providers = vec![
+            ProviderSpecification::from_trait(&trait_a).unwrap(),
+            ProviderSpecification::from_trait(&trait_b).unwrap(),
+        ];
+
+        let diagnostics = check_for_duplicate_names(&providers).err();
+        assert!(diagnostics.is_some());
+
+        let message = diagnostics.unwrap().into_prober_error().message;
+        assert!(message.contains("net_probes"));
+        assert!(message.contains("NetProbes"));
+    }
+
+    #[test]
+    fn allows_distinctly_named_providers() {
+        let trait_a: ItemTrait = parse_quote! {
+            trait NetProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+        let trait_b: ItemTrait = parse_quote! {
+            trait DbProbes {
+                fn probe0(arg0: i32);
+            }
+        };
+
+        let providers = vec![
+            ProviderSpecification::from_trait(&trait_a).unwrap(),
+            ProviderSpecification::from_trait(&trait_b).unwrap(),
+        ];
+
+        assert_eq!(true, check_for_duplicate_names(&providers).is_ok());
+    }
+
+    #[test]
+    fn detects_duplicate_probe_names_within_one_provider() {
+        let item_trait: ItemTrait = parse_quote! {
+            trait NetProbes {
+                fn probe0(arg0: i32);
+                fn probe0(arg0: &str);
+            }
+        };
+
+        // `find_probes`/`from_method` don't themselves reject the second `probe0` (they only look
+        // at each method in isolation), so this exercises `check_for_duplicate_names`'s own scan
+        // over the trait's items, independent of how many `ProbeSpecification`s were produced.
+        let token_stream = quote! { #item_trait };
+        let provider = ProviderSpecification {
+            name: "net_probes".to_string(),
+            hash: crate::hashing::hash_token_stream(&token_stream),
+            item_trait: item_trait.clone(),
+            token_stream,
+            probes: Vec::new(),
+            metadata: Vec::new(),
+            doc: None,
+        };
+
+        let diagnostics = check_for_duplicate_names(&[provider]).err();
+        assert!(diagnostics.is_some());
+        assert!(diagnostics.unwrap().into_prober_error().message.contains("probe0"));
+    }
+
+    #[test]
+    fn harvests_doc_comments_and_renders_markdown() {
+        let item_trait: ItemTrait = parse_quote! {
+            /// Probes fired while handling an order.
+            trait OrderProbes {
+                /// Fired when a new order is submitted.
+                fn order_submitted(order_id: u64, customer: &str);
+                fn order_shipped(order_id: u64);
+            }
+        };
+
+        let provider = ProviderSpecification::from_trait(&item_trait).unwrap();
+        assert_eq!(
+            Some("Probes fired while handling an order."),
+            provider.doc()
+        );
+
+        let markdown = generate_documentation_markdown(&provider);
+        assert!(markdown.contains("order_submitted"));
+        assert!(markdown.contains("order_probes:order_submitted"));
+        assert!(markdown.contains("Fired when a new order is submitted."));
+        assert!(markdown.contains("_undocumented_"));
+    }
+
+    #[test]
+    fn reports_undocumented_probes() {
+        let item_trait: ItemTrait = parse_quote! {
+            trait OrderProbes {
+                /// Fired when a new order is submitted.
+                fn order_submitted(order_id: u64);
+                fn order_shipped(order_id: u64);
+            }
+        };
+
+        assert_eq!(
+            vec!["order_shipped".to_string()],
+            undocumented_probe_names(&item_trait)
+        );
+    }
+
+    #[test]
+    fn composes_probes_from_supertraits() {
+        let net_trait: ItemTrait = parse_quote! {
+            #[prober]
+            trait NetProbes {
+                fn connection_opened(addr: &str);
+            }
+        };
+        let db_trait: ItemTrait = parse_quote! {
+            #[prober]
+            trait DbProbes {
+                fn query_started(query: &str);
+            }
+        };
+        let app_trait: ItemTrait = parse_quote! {
+            #[prober]
+            trait AppProbes: NetProbes + DbProbes {
+                fn request_handled(path: &str);
+            }
+        };
+
+        let known_providers = vec![
+            ProviderSpecification::from_trait(&net_trait).unwrap(),
+            ProviderSpecification::from_trait(&db_trait).unwrap(),
+        ];
+
+        let app_provider = from_trait_with_supertraits(&app_trait, &known_providers).unwrap();
+        assert_eq!(3, app_provider.probes().len());
+    }
+
+    #[test]
+    fn composes_probes_across_multiple_supertrait_levels() {
+        // `GrandProbes: AppProbes` where `AppProbes: NetProbes + DbProbes` itself -- three levels
+        // deep. Going through `find_providers`, rather than calling `from_trait_with_supertraits`
+        // directly, exercises the dependency-ordering that decides which providers are "known" by
+        // the time each trait is composed.
+        let test_file: syn::File = parse_quote! {
+            #[prober]
+            trait NetProbes {
+                fn connection_opened(addr: &str);
+            }
+
+            #[prober]
+            trait DbProbes {
+                fn query_started(query: &str);
+            }
+
+            #[prober]
+            trait AppProbes: NetProbes + DbProbes {
+                fn request_handled(path: &str);
+            }
+
+            #[prober]
+            trait GrandProbes: AppProbes {
+                fn request_completed(path: &str);
+            }
+        };
+
+        let providers = find_providers(&test_file);
+        let grand_provider = providers
+            .iter()
+            .find(|p| p.item_trait.ident.to_string() == "GrandProbes")
+            .expect("GrandProbes was not found among the composed providers");
+
+        // One probe of its own, plus `request_handled` from `AppProbes`, plus `connection_opened`
+        // and `query_started` inherited transitively through `AppProbes` from `NetProbes`/`DbProbes`.
+        assert_eq!(4, grand_provider.probes().len());
+    }
+
+    #[test]
+    fn supertrait_composition_rejects_non_provider_supertrait() {
+        let app_trait: ItemTrait = parse_quote! {
+            #[prober]
+            trait AppProbes: NotAProvider {
+                fn request_handled(path: &str);
+            }
+        };
+
+        let error = from_trait_with_supertraits(&app_trait, &[]).err();
+        assert_ne!(None, error);
+        assert!(error.unwrap().message.contains("NotAProvider"));
+    }
+
+    #[test]
+    fn supertrait_composition_rejects_colliding_probe_names() {
+        let net_trait: ItemTrait = parse_quote! {
+            #[prober]
+            trait NetProbes {
+                fn request_handled(addr: &str);
+            }
+        };
+        let app_trait: ItemTrait = parse_quote! {
+            #[prober]
+            trait AppProbes: NetProbes {
+                fn request_handled(path: &str);
+            }
+        };
+
+        let known_providers = vec![ProviderSpecification::from_trait(&net_trait).unwrap()];
+
+        let error = from_trait_with_supertraits(&app_trait, &known_providers).err();
+        assert_ne!(None, error);
+        assert!(error.unwrap().message.contains("request_handled"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,119 @@
+//! Shared fixtures for the `provider`/`proc_macros` test modules.  Each `TestProviderTrait` bundles
+//! a provider trait declaration together with the outcome it's expected to produce -- either the
+//! `ProbeSpecification`s that a correct implementation should derive from it, or the substring of
+//! the compile error it should produce instead -- so that both the trait-discovery tests in
+//! `provider` and the full `prober_impl` expansion tests in `proc_macros` can run the same inputs
+//! through their respective entry points and check the result against one shared expectation.
+use crate::probe::ProbeSpecification;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ItemTrait, TraitItem};
+
+pub(crate) struct TestProviderTrait {
+    /// Human-readable description of what this fixture exercises, used in test failure messages
+    pub(crate) description: &'static str,
+    /// The trait declaration itself, to be spliced into a `#[prober] trait ... { ... }` item
+    pub(crate) tokenstream: TokenStream,
+    /// The snake_case provider name a correct implementation should derive from the trait's ident
+    pub(crate) provider_name: String,
+    /// `Some(probes)` if this trait is valid and should produce exactly these probes; `None` if
+    /// `expected_error` is set instead
+    pub(crate) probes: Option<Vec<ProbeSpecification>>,
+    /// `Some(substring)` if this trait is invalid and analysis should fail with an error containing
+    /// this substring; `None` if the trait is valid
+    pub(crate) expected_error: Option<&'static str>,
+}
+
+/// Returns every fixture trait for which `filter` returns `true`.  Callers typically partition on
+/// whether `expected_error` is set, to separate the "should succeed" fixtures from the "should fail"
+/// ones.
+pub(crate) fn get_test_provider_traits(
+    filter: impl Fn(&TestProviderTrait) -> bool,
+) -> Vec<TestProviderTrait> {
+    all_test_provider_traits()
+        .into_iter()
+        .filter(filter)
+        .collect()
+}
+
+fn probes_of(item: &ItemTrait) -> Vec<ProbeSpecification> {
+    item.items
+        .iter()
+        .filter_map(|i| match i {
+            TraitItem::Method(m) => Some(
+                ProbeSpecification::from_method(item, m)
+                    .expect("fixture trait methods must themselves be valid probes"),
+            ),
+            _ => None,
+        })
+        .collect()
+}
+
+fn all_test_provider_traits() -> Vec<TestProviderTrait> {
+    let simple_valid: ItemTrait = syn::parse_quote! {
+        trait SimpleProbes {
+            fn probe0(arg0: i32);
+            fn probe1(arg0: &str);
+        }
+    };
+    let simple_valid_probes = probes_of(&simple_valid);
+
+    let valid_with_many_refs: ItemTrait = syn::parse_quote! {
+        trait ManyRefProbes {
+            fn probe0(arg0: i32);
+            fn probe1(arg0: &str, arg1: &usize, arg2: &Option<i32>);
+        }
+    };
+    let valid_with_many_refs_probes = probes_of(&valid_with_many_refs);
+
+    vec![
+        TestProviderTrait {
+            description: "a simple trait with scalar and reference args",
+            tokenstream: quote! { #simple_valid },
+            provider_name: "simple_probes".to_string(),
+            probes: Some(simple_valid_probes),
+            expected_error: None,
+        },
+        TestProviderTrait {
+            description: "a trait with several reference-typed probe args",
+            tokenstream: quote! { #valid_with_many_refs },
+            provider_name: "many_ref_probes".to_string(),
+            probes: Some(valid_with_many_refs_probes),
+            expected_error: None,
+        },
+        TestProviderTrait {
+            description: "a trait with a type parameter",
+            tokenstream: quote! {
+                trait GenericProbes<T: std::fmt::Debug> {
+                    fn probe0(arg0: T);
+                }
+            },
+            provider_name: "generic_probes".to_string(),
+            probes: None,
+            expected_error: Some("must not take any lifetime or type parameters"),
+        },
+        TestProviderTrait {
+            description: "a trait containing a non-method item",
+            tokenstream: quote! {
+                trait ProbesWithConst {
+                    const FOO: usize = 5;
+                    fn probe0(arg0: i32);
+                }
+            },
+            provider_name: "probes_with_const".to_string(),
+            probes: None,
+            expected_error: Some("must consist entirely of methods"),
+        },
+        TestProviderTrait {
+            description: "a probe taking an owned `String`, which isn't `Copy`",
+            tokenstream: quote! {
+                trait OwnedStringProbes {
+                    fn probe0(arg0: String);
+                }
+            },
+            provider_name: "owned_string_probes".to_string(),
+            probes: None,
+            expected_error: Some("Copy"),
+        },
+    ]
+}
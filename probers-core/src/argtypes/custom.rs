@@ -44,51 +44,105 @@ where
     }
 }
 
-//impl<CustomT, PrimitiveT> ProbeArgType<CustomT> for CustomT
-//where
-//    CustomT: CustomProbeArgType<PrimitiveT>,
-//    PrimitiveT: ProbeArgType<PrimitiveT> + Sized + Debug,
-//{
-//    type WrapperType = CustomProbeArgWrapper<
-//        CustomT,
-//        PrimitiveT,
-//        <PrimitiveT as ProbeArgType<PrimitiveT>>::WrapperType,
-//    >;
-//}
-//
-//pub struct CustomProbeArgWrapper<CustomT, PrimitiveT, PrimitiveWrapperT>
-//where
-//    CustomT: Into<PrimitiveT> + Sized,
-//    PrimitiveWrapperT: ProbeArgWrapper<PrimitiveT>,
-//    PrimitiveT: ProbeArgType<PrimitiveT> + Debug,
-//{
-//    arg: CustomT,
-//    wrapper: Option<PrimitiveWrapperT>,
-//}
-//
-//impl<CustomT, PrimitiveT, PrimitiveWrapperT> std::fmt::Debug
-//    for CustomProbeArgWrapper<CustomT, PrimitiveT, PrimitiveWrapperT>
-//where
-//    CustomT: Into<PrimitiveT>,
-//    PrimitiveWrapperT: ProbeArgWrapper<PrimitiveT>,
-//    PrimitiveT: ProbeArgType<PrimitiveT> + Debug,
-//{
-//}
-//
-//impl<CustomT, PrimitiveT, PrimitiveWrapperT> ProbeArgWrapper<CustomT>
-//    for CustomProbeArgWrapper<CustomT, PrimitiveT, PrimitiveWrapperT>
-//where
-//    CustomT: Into<PrimitiveT>,
-//    PrimitiveWrapperT: ProbeArgWrapper<PrimitiveT>,
-//    PrimitiveT: ProbeArgType<PrimitiveT> + Debug,
-//{
-//    type CType = PrimitiveWrapperT::CType;
-//
-//    fn to_c_type(&mut self) -> Self::CType {
-//        let wrapped = super::wrap::<PrimitiveT>(self.0.into());
-//        wrapped.to_c_type()
-//    }
-//    fn default_c_value() -> Self::CType {
-//        PrimitiveWrapperT::default_c_value()
-//    }
-//}
+/// Marker trait implemented by a user's own type (typically an enum or newtype) to declare that it
+/// can be probed by lowering itself to one of the primitive types `P` that `probers` already knows
+/// how to wrap.
+///
+/// A bare blanket impl of `ProbeArgType<CustomT> for CustomT where CustomT: Into<P>` would collide
+/// under coherence with the existing `&str`/integer/`Option` impls of `ProbeArgType`: nothing would
+/// stop those primitives from also satisfying the blanket bound.  Requiring this marker trait
+/// instead means the user opts in explicitly for each custom type, which sidesteps the coherence
+/// conflict entirely.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Copy, Clone, Debug)]
+/// enum Status {
+///     Ok,
+///     Error,
+/// }
+///
+/// impl Into<i32> for Status {
+///     fn into(self) -> i32 {
+///         match self {
+///             Status::Ok => 0,
+///             Status::Error => 1,
+///         }
+///     }
+/// }
+///
+/// impl CustomProbeArgType<i32> for Status {}
+///
+/// // `Status` can now be used directly as a probe argument:
+/// // probe!(MyProvider::event(my_status));
+/// ```
+pub trait CustomProbeArgType<P>: Copy + Into<P>
+where
+    P: ProbeArgType<P> + Debug,
+{
+}
+
+impl<CustomT, PrimitiveT> ProbeArgType<CustomT> for CustomT
+where
+    CustomT: CustomProbeArgType<PrimitiveT> + Debug,
+    PrimitiveT: ProbeArgType<PrimitiveT> + Sized + Debug,
+{
+    type WrapperType = CustomProbeArgWrapper<
+        CustomT,
+        PrimitiveT,
+        <PrimitiveT as ProbeArgType<PrimitiveT>>::WrapperType,
+    >;
+}
+
+pub struct CustomProbeArgWrapper<CustomT, PrimitiveT, PrimitiveWrapperT>
+where
+    CustomT: CustomProbeArgType<PrimitiveT> + Debug,
+    PrimitiveWrapperT: ProbeArgWrapper<PrimitiveT>,
+    PrimitiveT: ProbeArgType<PrimitiveT> + Debug,
+{
+    arg: CustomT,
+    _marker: std::marker::PhantomData<(PrimitiveT, PrimitiveWrapperT)>,
+}
+
+impl<CustomT, PrimitiveT, PrimitiveWrapperT> std::fmt::Debug
+    for CustomProbeArgWrapper<CustomT, PrimitiveT, PrimitiveWrapperT>
+where
+    CustomT: CustomProbeArgType<PrimitiveT> + Debug,
+    PrimitiveWrapperT: ProbeArgWrapper<PrimitiveT>,
+    PrimitiveT: ProbeArgType<PrimitiveT> + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        //Just use the Debug impl on the value this wraps, same as FuncProbeArgTypeWrapper does
+        self.arg.fmt(f)
+    }
+}
+
+impl<CustomT, PrimitiveT, PrimitiveWrapperT> ProbeArgWrapper<CustomT>
+    for CustomProbeArgWrapper<CustomT, PrimitiveT, PrimitiveWrapperT>
+where
+    CustomT: CustomProbeArgType<PrimitiveT> + Debug,
+    PrimitiveWrapperT: ProbeArgWrapper<PrimitiveT>,
+    PrimitiveT: ProbeArgType<PrimitiveT> + Debug,
+{
+    type CType = PrimitiveWrapperT::CType;
+
+    fn new(arg: CustomT) -> Self {
+        CustomProbeArgWrapper {
+            arg,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn to_c_type(&mut self) -> Self::CType {
+        //Convert the custom value down to the primitive it declares via `CustomProbeArgType`, then
+        //delegate to that primitive's own wrapper, exactly as `FuncProbeArgTypeWrapper` delegates
+        //to `super::wrap` for the value its closure produces.
+        let mut wrapped = super::wrap::<PrimitiveT>(self.arg.into());
+        wrapped.to_c_type()
+    }
+
+    fn default_c_value() -> Self::CType {
+        PrimitiveWrapperT::default_c_value()
+    }
+}
@@ -0,0 +1,115 @@
+use super::{ProbeArgType, ProbeArgWrapper};
+use std::ffi::CString;
+use std::fmt;
+
+/// Wraps a probe argument so it's recorded by its `Display` rendering (the `%arg` convention from
+/// `tracing`) instead of requiring the wrapped type to implement `ProbeArgType` itself.  This gives
+/// callers control over how a rich type shows up in `bpftrace` output without forcing every type to
+/// plumb through the primitive argument machinery.
+///
+/// ```ignore
+/// probe!(MyProvider::event(DisplayArg::new(some_url)));
+/// ```
+pub struct DisplayArg<T: fmt::Display>(T);
+
+impl<T: fmt::Display> DisplayArg<T> {
+    pub fn new(value: T) -> Self {
+        DisplayArg(value)
+    }
+}
+
+/// `DisplayArg` forwards its own `Debug` impl to the wrapped value's `Display` impl.  This is what
+/// lets the lazy-evaluation form, `&Fn() -> DisplayArg<T>`, work for free: `FuncProbeArgTypeWrapper`
+/// only requires its return type to implement `ProbeArgType<T> + Debug`, and `DisplayArg<T>`
+/// already satisfies both without any further wrapper code.
+impl<T: fmt::Display> fmt::Debug for DisplayArg<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<T: fmt::Display> ProbeArgType<DisplayArg<T>> for DisplayArg<T> {
+    type WrapperType = DisplayArgWrapper<T>;
+}
+
+pub struct DisplayArgWrapper<T: fmt::Display> {
+    rendered: CString,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: fmt::Display> ProbeArgWrapper<DisplayArg<T>> for DisplayArgWrapper<T> {
+    type CType = *const std::os::raw::c_char;
+
+    fn new(arg: DisplayArg<T>) -> Self {
+        let rendered = CString::new(format!("{}", arg.0)).unwrap_or_else(|_| {
+            CString::new("<value contains a NUL byte>").expect("static string has no NUL byte")
+        });
+
+        DisplayArgWrapper {
+            rendered,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn to_c_type(&mut self) -> Self::CType {
+        self.rendered.as_ptr()
+    }
+
+    fn default_c_value() -> Self::CType {
+        std::ptr::null()
+    }
+}
+
+/// Wraps a probe argument so it's recorded by its `Debug` rendering (the `?arg` convention from
+/// `tracing`), the same way probe arguments are already rendered when passed through
+/// `FuncProbeArgTypeWrapper`.  Useful when a caller wants to be explicit about wanting `Debug`
+/// formatting for a type that also happens to implement `ProbeArgType` some other way.
+///
+/// ```ignore
+/// probe!(MyProvider::event(DebugArg::new(some_struct)));
+/// ```
+pub struct DebugArg<T: fmt::Debug>(T);
+
+impl<T: fmt::Debug> DebugArg<T> {
+    pub fn new(value: T) -> Self {
+        DebugArg(value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DebugArg<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Debug> ProbeArgType<DebugArg<T>> for DebugArg<T> {
+    type WrapperType = DebugArgWrapper<T>;
+}
+
+pub struct DebugArgWrapper<T: fmt::Debug> {
+    rendered: CString,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: fmt::Debug> ProbeArgWrapper<DebugArg<T>> for DebugArgWrapper<T> {
+    type CType = *const std::os::raw::c_char;
+
+    fn new(arg: DebugArg<T>) -> Self {
+        let rendered = CString::new(format!("{:?}", arg.0)).unwrap_or_else(|_| {
+            CString::new("<value contains a NUL byte>").expect("static string has no NUL byte")
+        });
+
+        DebugArgWrapper {
+            rendered,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn to_c_type(&mut self) -> Self::CType {
+        self.rendered.as_ptr()
+    }
+
+    fn default_c_value() -> Self::CType {
+        std::ptr::null()
+    }
+}
@@ -0,0 +1,43 @@
+use super::{ProbeArgType, ProbeArgWrapper};
+
+/// Wraps a `&[T]` probe argument by lowering it to a `(*const T, usize)` pair -- a pointer to the
+/// first element plus the element count -- instead of requiring `T` to be `Copy` and passed
+/// element-by-element.  This is the standard FFI-boundary lowering for a borrowed buffer, and it's
+/// what lets a probe carry the contents of e.g. a request body without the caller having to split
+/// it into separate pointer and length arguments by hand.
+///
+/// **This impl is not reachable from the `#[prober]` macro yet.** Splitting `CType` into the two
+/// native probe fields it needs is the job of `ProbeSpecification`'s own codegen (the
+/// `generate_struct_member_declaration`/`generate_add_probe_call` family), since that's the only
+/// place that knows how many native probe fields a given Rust argument type lowers to. That's
+/// tracked as a separate follow-up; until it lands, a `&[T]` probe argument type-checks against
+/// `ProbeArgType` but can't actually be fired through the generated macro pipeline.
+impl<'a, T> ProbeArgType<&'a [T]> for &'a [T] {
+    type WrapperType = SliceArgWrapper<'a, T>;
+}
+
+#[derive(Debug)]
+pub struct SliceArgWrapper<'a, T> {
+    slice: &'a [T],
+}
+
+impl<'a, T> ProbeArgWrapper<&'a [T]> for SliceArgWrapper<'a, T> {
+    type CType = (*const T, usize);
+
+    fn new(arg: &'a [T]) -> Self {
+        SliceArgWrapper { slice: arg }
+    }
+
+    fn to_c_type(&mut self) -> Self::CType {
+        (self.slice.as_ptr(), self.slice.len())
+    }
+
+    fn default_c_value() -> Self::CType {
+        (std::ptr::null(), 0)
+    }
+}
+
+// `&str` already has a `ProbeArgType` impl (it's one of the primitives this crate recognizes
+// natively, alongside the integer and `Option` impls -- see the coherence note on
+// `CustomProbeArgType` in `custom.rs`), so there's no second impl to add here; `&[T]` is the only
+// borrowed-buffer type that needed this pointer+length lowering.
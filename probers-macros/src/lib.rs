@@ -13,10 +13,11 @@ use proc_macro::TokenStream as CompilerTokenStream;
 use proc_macro2::{Span, TokenStream};
 use proc_macro_hack::proc_macro_hack;
 use quote::{quote, quote_spanned};
-use std::borrow::BorrowMut;
+use syn::parse::{Parse, ParseStream};
 use syn::parse_quote;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Ident, ItemTrait, TraitItem};
+use syn::{parse_macro_input, FnArg, Ident, ItemTrait, Token, TraitItem};
 
 #[proc_macro_attribute]
 pub fn prober(_attr: CompilerTokenStream, item: CompilerTokenStream) -> CompilerTokenStream {
@@ -24,18 +25,33 @@ pub fn prober(_attr: CompilerTokenStream, item: CompilerTokenStream) -> Compiler
     // will cause what looks to the user like a compile error complaining that it expected a trait.
     let input = parse_macro_input!(item as ItemTrait);
 
-    match prober_impl(input) {
+    match prober_impl(input.clone()) {
         Ok(stream) => stream,
-        Err(err) => report_error(&err.message, err.span),
+        Err(err) => {
+            // Emitting just the `compile_error!` here would leave the `struct #ident` and its
+            // methods undefined, so every other place that references this provider --
+            // `probe!`, `init_provider!`, calls to its own methods -- would *also* fail to
+            // resolve, burying the one real error under a flood of spurious "cannot find" noise.
+            // Emit a dummy struct alongside the error so name resolution still succeeds and the
+            // user sees only the genuine diagnostic.
+            let error = report_error(&err.message, err.span);
+            let dummy = generate_dummy_struct(&input);
+
+            quote! {
+                #error
+
+                #dummy
+            }
+        }
     }
     .into()
 }
 
 #[proc_macro_hack]
 pub fn probe(input: CompilerTokenStream) -> CompilerTokenStream {
-    let input = parse_macro_input!(input as syn::Expr);
+    let call_spec = parse_macro_input!(input as ProbeCallSpecification);
 
-    match probe_impl(input) {
+    match probe_impl(call_spec) {
         Ok(stream) => stream,
         Err(err) => report_error(&err.message, err.span),
     }
@@ -59,38 +75,132 @@ impl ProberError {
 
 type ProberResult<T> = std::result::Result<T, ProberError>;
 
-fn probe_impl(call: syn::Expr) -> ProberResult<TokenStream> {
-    match call {
-        syn::Expr::Call(mut call) => {
-            //`call` is a `syn::ExprCall` struct.  It contains a `Box<Path>` where which specifies
-            //the name of the function being called in the form of a (possibly fully or partially)
-            //qualified path.  We will take this almost entirely as-is, but with the slight
-            //adjustment that we want the name of the function being called (which is the probe to
-            //fire) should have the "_impl" appended.
-            if let syn::Expr::Path(ref mut func) = call.func.borrow_mut() {
-                if let Some(ref mut pair) = func.path.segments.last_mut() {
-                    let mut probe_segment = pair.value_mut();
-                    probe_segment.ident =
-                        syn_helpers::add_suffix_to_ident(&probe_segment.ident, "_impl");
-                }
-            } else {
-                return Err(ProberError::new(
-                    format!("Unexpected expression for function call: {:?}", call.func),
-                    call.span(),
-                ));
-            }
+/// The parsed, normalized form of whatever was passed to the `probe!` macro: an optional leading
+/// guard expression, followed by a call to a provider's probe method, e.g.
+///
+/// ```noexecute
+/// probe!(MyProvider::myprobe(1, 2));
+/// probe!(level >= 3, crate::probes::MyProvider::myprobe(1, 2));
+/// ```
+///
+/// Keeping this as an explicit struct -- rather than pattern-matching a `syn::Expr` in place, as
+/// `probe_impl` used to -- makes the grammar `probe!` actually accepts explicit, and gives each part
+/// (the guard, the provider path, the probe name, the arguments) its own span-accurate diagnostics
+/// instead of one generic "unexpected expression" error.
+struct ProbeCallSpecification {
+    /// The whole invocation's span, used for diagnostics that don't point at any more specific part
+    span: Span,
+    /// The optional guard expression before the probe call, e.g. `level >= 3` in
+    /// `probe!(level >= 3, MyProvider::myprobe())`.  When present, the probe only fires if this
+    /// expression evaluates to `true`, in addition to the is-enabled check.
+    condition: Option<syn::Expr>,
+    /// The provider's path, not including the probe method's own name, e.g. `crate::probes::MyProvider`
+    provider_path: syn::Path,
+    /// The name of the probe method itself, e.g. `myprobe`
+    probe_name: Ident,
+    /// The arguments passed to the probe
+    args: Punctuated<syn::Expr, Token![,]>,
+}
 
-            Ok(quote! { #call })
-        }
-        _ => {
-            return Err(ProberError::new(
+impl Parse for ProbeCallSpecification {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let span = input.cursor().span();
+        let exprs: Punctuated<syn::Expr, Token![,]> =
+            input.parse_terminated(syn::Expr::parse)?;
+
+        let (condition, call) = match exprs.len() {
+            1 => (None, exprs.into_iter().next().unwrap()),
+            2 => {
+                let mut iter = exprs.into_iter();
+                let condition = iter.next().unwrap();
+                let call = iter.next().unwrap();
+                (Some(condition), call)
+            }
+            0 => {
+                return Err(syn::Error::new(
+                    span,
                     "The probe! macro requires the name of a provider trait and its probe method, e.g. MyProvider::myprobe(...)",
-                    call.span(),
-                    ));
+                ));
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "The probe! macro takes at most an optional leading condition and the call to the probe method, e.g. probe!(cond, MyProvider::myprobe(...))",
+                ));
+            }
+        };
+
+        let call = match call {
+            syn::Expr::Call(call) => call,
+            other => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    format!("expected Provider::probe(...), found {:?}", other),
+                ));
+            }
+        };
+
+        let mut provider_path = match *call.func {
+            syn::Expr::Path(path) => path.path,
+            other => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    "expected Provider::probe, found a method call or other non-path expression",
+                ));
+            }
+        };
+
+        let probe_name = match provider_path.segments.pop() {
+            Some(pair) => pair.into_value().ident,
+            None => {
+                return Err(syn::Error::new(
+                    provider_path.span(),
+                    "expected Provider::probe, found an empty path",
+                ));
+            }
+        };
+        // Popping the last segment leaves the new last segment with a dangling trailing `::`;
+        // pop it and push it back bare so the path renders correctly when quoted.
+        if let Some(pair) = provider_path.segments.pop() {
+            provider_path.segments.push_value(pair.into_value());
         }
+
+        Ok(ProbeCallSpecification {
+            span,
+            condition,
+            provider_path,
+            probe_name,
+            args: call.args,
+        })
     }
 }
 
+fn probe_impl(spec: ProbeCallSpecification) -> ProberResult<TokenStream> {
+    let provider_path = &spec.provider_path;
+    let enabled_name = syn_helpers::add_suffix_to_ident(&spec.probe_name, "_enabled");
+    let impl_name = syn_helpers::add_suffix_to_ident(&spec.probe_name, "_impl");
+    let args = &spec.args;
+
+    let fire_call = quote_spanned! { spec.span =>
+        #provider_path::#impl_name(#args)
+    };
+
+    let guard = match &spec.condition {
+        Some(condition) => quote_spanned! { spec.span =>
+            #provider_path::#enabled_name() && (#condition)
+        },
+        None => quote_spanned! { spec.span =>
+            #provider_path::#enabled_name()
+        },
+    };
+
+    Ok(quote_spanned! { spec.span =>
+        if #guard {
+            #fire_call
+        }
+    })
+}
+
 /// Actual implementation of the macro logic, factored out of the proc macro itself so that it's
 /// more testable
 fn prober_impl(item: ItemTrait) -> ProberResult<TokenStream> {
@@ -108,16 +218,80 @@ fn prober_impl(item: ItemTrait) -> ProberResult<TokenStream> {
     let probe_struct = generate_prober_struct(&item, &probes)?;
 
     // Generate code for a struct and some `OnceCell` statics to hold the instance of the provider
-    // and individual probe wrappers
-    let impl_mod = generate_impl_mod(&item, &probes);
+    // and individual probe wrappers -- unless the `noop` backend is selected, in which case the
+    // provider's `get()` always returns `None`, which makes every probe method's existing "is
+    // there a provider instance" check skip firing, and the whole `SystemProvider`/`OnceCell`
+    // machinery compiles away to nothing.  The noop struct still needs the same fields as the real
+    // one, since `probe.generate_trait_methods` generates one shared set of `_impl`/`_enabled`
+    // bodies for both backends, and those bodies access the fields through whatever `get()`
+    // returns -- rustc type-checks that access regardless of `get()` always yielding `None`.
+    let impl_mod = if cfg!(feature = "noop") {
+        generate_noop_impl_mod(&item, &probes)
+    } else {
+        generate_impl_mod(&item, &probes)
+    };
+
+    // Emit one compile-time assertion per probe that its argument types are all usable as probe
+    // arguments, so a probe declared with an unsupported type fails here with a readable error
+    // pointing at the argument, instead of several levels down in `impl_mod`
+    let arg_type_assertions = generate_arg_type_assertions(&item);
 
     Ok(quote_spanned! { item.span() =>
         #probe_struct
 
         #impl_mod
+
+        #arg_type_assertions
     })
 }
 
+/// For each probe method, emits a free function that instantiates a helper bounded on
+/// `ProbeArgType` once for every one of its argument types.  (The bound isn't stated in a `where`
+/// clause, since reference argument types like `&str` carry elided lifetimes, which rustc rejects
+/// there.)  This can't be a simple `compile_error!` the way the other validations in
+/// this module are, because whether a concrete type implements `ProbeArgType` isn't something the
+/// macro can determine by looking at the AST -- it depends on `probers-core`'s trait impls,
+/// including any user-defined `CustomProbeArgType` impls the macro has no way to see.  Only the real
+/// type checker can resolve that, so instead of trying to replicate it here, this just states the
+/// bound and lets rustc's own trait-resolution diagnostics report the problem, with the span
+/// pointing at the declared argument type that's missing an impl.
+fn generate_arg_type_assertions(item: &ItemTrait) -> TokenStream {
+    let mut assertions = TokenStream::new();
+
+    for f in item.items.iter() {
+        if let TraitItem::Method(ref m) = f {
+            let method_name = &m.sig.ident;
+            let assert_fn_name = Ident::new(
+                &format!("__probers_assert_{}_args_are_probe_arg_types", method_name),
+                method_name.span(),
+            );
+            let arg_types: Vec<&syn::Type> = m
+                .sig
+                .inputs
+                .iter()
+                .filter_map(|arg| match arg {
+                    FnArg::Typed(pat_type) => Some(&*pat_type.ty),
+                    FnArg::Receiver(_) => None,
+                })
+                .collect();
+
+            if arg_types.is_empty() {
+                continue;
+            }
+
+            assertions.extend(quote_spanned! { method_name.span() =>
+                #[allow(non_snake_case, dead_code)]
+                fn #assert_fn_name() {
+                    fn assert_probe_arg_type<T: ::probers_core::ProbeArgType<T>>() {}
+                    #(assert_probe_arg_type::<#arg_types>();)*
+                }
+            });
+        }
+    }
+
+    assertions
+}
+
 /// A provider is described by the user as a `trait`, with methods corresponding to probes.
 /// However it's actually implemented as a `struct` with no member fields, with static methods
 /// implementing the probes.  Thus, given as input the `trait`, we produce a `struct` of the same
@@ -127,7 +301,10 @@ fn generate_prober_struct(
     probes: &Vec<ProbeSpecification>,
 ) -> ProberResult<TokenStream> {
     // From the probe specifications, generate the corresponding methods that will be on the probe
-    // struct.
+    // struct.  Alongside each probe's existing `_impl` method (the one that actually fires it),
+    // `generate_trait_methods` also generates a paired `_enabled` method, backed by the
+    // `SystemProbe`'s semaphore, which `probe_impl` calls to guard evaluation of the probe's
+    // arguments.
     let mut probe_methods: Vec<TokenStream> = Vec::new();
     let mod_name = get_provider_impl_mod_name(&item.ident);
     let struct_type_name = get_provider_impl_struct_type_name(&item.ident);
@@ -161,6 +338,69 @@ fn generate_prober_struct(
     Ok(result)
 }
 
+/// Generates a stand-in for the provider struct that `generate_prober_struct` would otherwise
+/// produce, for use when codegen fails partway through.  Per probe it has the `<probe>_enabled()` /
+/// `<probe>_impl(...)` pair that `probe_impl` expands to, plus a `get_<probe>_probe()` accessor;
+/// it also has `__try_init_provider()` and `__get_init_error()`.  Every method returns `false` or
+/// `None`, or does nothing, since there's no real provider to back it.  The shape doesn't matter,
+/// only that it type-checks and has the right names, so that the
+/// rest of the crate (and any downstream crate using `probe!`/`init_provider!` on this provider)
+/// still resolves, leaving only the genuine `compile_error!` for the user to see.
+fn generate_dummy_struct(item: &ItemTrait) -> TokenStream {
+    let span = item.span();
+    let ident = &item.ident;
+    let vis = &item.vis;
+
+    let probe_accessors: Vec<TokenStream> = item
+        .items
+        .iter()
+        .filter_map(|i| match i {
+            TraitItem::Method(m) => Some(&m.sig),
+            _ => None,
+        })
+        .map(|sig| {
+            let probe_name = &sig.ident;
+            let accessor_name = Ident::new(&format!("get_{}_probe", probe_name), probe_name.span());
+            let enabled_name = syn_helpers::add_suffix_to_ident(probe_name, "_enabled");
+            let impl_name = syn_helpers::add_suffix_to_ident(probe_name, "_impl");
+            let generics = &sig.generics;
+            let inputs = &sig.inputs;
+            quote_spanned! { probe_name.span() =>
+                #[allow(dead_code)]
+                fn #accessor_name() -> Option<()> {
+                    None
+                }
+
+                #[allow(dead_code)]
+                fn #enabled_name() -> bool {
+                    false
+                }
+
+                #[allow(dead_code, unused_variables)]
+                fn #impl_name #generics (#inputs) {}
+            }
+        })
+        .collect();
+
+    quote_spanned! { span =>
+        #vis struct #ident;
+
+        impl #ident {
+            #(#probe_accessors)*
+
+            #[allow(dead_code)]
+            fn __try_init_provider() -> Option<&'static str> {
+                None
+            }
+
+            #[allow(dead_code)]
+            fn __get_init_error() -> Option<&'static ::failure::Error> {
+                None
+            }
+        }
+    }
+}
+
 /// Looking at the methods defined on the trait, deduce from those methods the probes that we will
 /// need to define, including their arg counts and arg types.
 ///
@@ -185,6 +425,55 @@ fn get_probes(item: &ItemTrait) -> ProberResult<Vec<ProbeSpecification>> {
     Ok(specs)
 }
 
+/// The backend used when the `noop` Cargo feature is enabled: a stand-in for `generate_impl_mod`
+/// with the same public surface (`get()`/`get_init_error()`) and the same struct fields, but none
+/// of the machinery needed to actually back a provider.  `get()` always returns `None`, so every
+/// probe method's existing check for "is there a provider instance to fire against" takes the same
+/// path it already takes when a real backend fails to initialize -- the probe call compiles down to
+/// a single always-false branch instead of touching `SystemTracer`, `SystemProvider`, or any
+/// `OnceCell` statics at all.
+///
+/// The struct can't simply drop its fields the way `generate_dummy_struct` drops its probe
+/// accessors: `probe.generate_trait_methods` emits one set of `_impl`/`_enabled` bodies shared by
+/// both backends, and those bodies access the struct's fields through whatever `Option<&Self>`
+/// `get()` hands back. Rustc type-checks that field access whether or not `get()` ever actually
+/// returns `Some`, so the noop struct needs the real field shape -- it just never gets instantiated.
+fn generate_noop_impl_mod(item: &ItemTrait, probes: &Vec<ProbeSpecification>) -> TokenStream {
+    let mod_name = get_provider_impl_mod_name(&item.ident);
+    let struct_type_name = get_provider_impl_struct_type_name(&item.ident);
+    let struct_type_params = get_provider_struct_type_params(probes);
+    let struct_members: Vec<_> = probes
+        .iter()
+        .map(|probe| probe.generate_struct_member_declaration())
+        .collect();
+
+    quote_spanned! { item.span() =>
+        mod #mod_name {
+            use ::probers::{ProviderProbe, SystemProbe};
+
+            #[allow(dead_code)]
+            pub(super) struct #struct_type_name<#struct_type_params> {
+                #(pub #struct_members),*
+            }
+
+            unsafe impl<#struct_type_params> Send for #struct_type_name<#struct_type_params> {}
+            unsafe impl<#struct_type_params> Sync for #struct_type_name<#struct_type_params> {}
+
+            impl<#struct_type_params> #struct_type_name<#struct_type_params> {
+                #[allow(dead_code)]
+                pub(super) fn get() -> Option<&'static #struct_type_name<#struct_type_params>> {
+                    None
+                }
+
+                #[allow(dead_code)]
+                pub(super) fn get_init_error() -> Option<&'static ::failure::Error> {
+                    None
+                }
+            }
+        }
+    }
+}
+
 /// The implementation of the probing logic is complex enough that it involves the declaration of a
 /// few variables and one new struct type.  All of this is contained within a module, to avoid the
 /// possibility of collissions with other code.  This method generates that module and all its
@@ -195,6 +484,8 @@ fn get_probes(item: &ItemTrait) -> ProberResult<Vec<ProbeSpecification>> {
 /// * A declaration of a `struct` which will hold references to all of the probes
 /// * Multiple static `OnceCell` variables which hold the underlying provider instance as well as
 /// the instance of the `struct` which holds references to all of the probes
+///
+/// See `generate_noop_impl_mod` for the zero-cost stand-in used when the `noop` backend is selected.
 fn generate_impl_mod(item: &ItemTrait, probes: &Vec<ProbeSpecification>) -> TokenStream {
     let mod_name = get_provider_impl_mod_name(&item.ident);
     let struct_type_name = get_provider_impl_struct_type_name(&item.ident);
@@ -531,6 +822,86 @@ mod test {
             }
         }
 
+        // The fixtures above each exercise `prober_impl` against a single trait in isolation.  The
+        // ones below bundle multiple traits and `probe!` call sites into one `syn::File`, the way a
+        // real crate would, so the tests further down can check the things a single-trait fixture
+        // can't: that two providers expanded in the same crate don't collide on module or struct
+        // names, and that a `probe!` call site parses and expands against a provider declared
+        // elsewhere in the same file.  (A real multi-crate fixture, each with its own `Cargo.toml`,
+        // would exercise this more realistically still, but this tree has no Cargo workspace wired
+        // up to build fixture crates in isolation, so a multi-item file is the closest equivalent
+        // that these tests can actually run.)
+        pub(crate) fn multi_trait_file_with_call_sites() -> syn::File {
+            parse_quote! {
+                trait OrderProbes {
+                    fn order_submitted(order_id: u64, customer: &str);
+                    fn order_shipped(order_id: u64);
+                }
+
+                trait ShipmentProbes {
+                    fn shipment_dispatched(shipment_id: u64, carrier: &str, weight: &f64);
+                }
+
+                fn track_order(order_id: u64, customer: &str) {
+                    probe!(OrderProbes::order_submitted(order_id, customer));
+                    probe!(order_id > 0, OrderProbes::order_shipped(order_id));
+                }
+
+                fn track_shipment(shipment_id: u64, carrier: &str, weight: &f64) {
+                    probe!(ShipmentProbes::shipment_dispatched(shipment_id, carrier, weight));
+                }
+            }
+        }
+
+        pub(crate) fn invalid_call_site_empty() -> TokenStream {
+            quote! {}
+        }
+
+        pub(crate) fn invalid_call_site_not_a_call() -> TokenStream {
+            quote! { some_provider_module::MyProvider }
+        }
+
+        pub(crate) fn invalid_call_site_method_call() -> TokenStream {
+            quote! { provider.myprobe(1, 2) }
+        }
+
+        pub(crate) fn invalid_call_site_too_many_args() -> TokenStream {
+            quote! { some_condition, another_condition, MyProvider::myprobe(1) }
+        }
+    }
+
+    /// Pulls every top-level `trait` out of a `syn::File`, in declaration order, for feeding
+    /// one-by-one into `prober_impl`.
+    fn extract_item_traits(file: &syn::File) -> Vec<ItemTrait> {
+        file.items
+            .iter()
+            .filter_map(|i| match i {
+                syn::Item::Trait(t) => Some(t.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Walks every item in a `syn::File`, including fn bodies, collecting the token stream inside
+    /// each `probe!(...)` invocation, for feeding one-by-one into `probe_impl`.
+    fn extract_probe_invocations(file: &syn::File) -> Vec<TokenStream> {
+        struct Visitor {
+            invocations: Vec<TokenStream>,
+        }
+
+        impl<'ast> syn::visit::Visit<'ast> for Visitor {
+            fn visit_macro(&mut self, m: &'ast syn::Macro) {
+                if m.path.is_ident("probe") {
+                    self.invocations.push(m.tokens.clone());
+                }
+            }
+        }
+
+        let mut visitor = Visitor {
+            invocations: Vec::new(),
+        };
+        syn::visit::Visit::visit_file(&mut visitor, file);
+        visitor.invocations
     }
 
     #[test]
@@ -591,4 +962,65 @@ mod test {
         // doesn't make sense for the caller to provide their own
         assert_eq!(true, prober_impl(data::has_default_impl()).is_err());
     }
+
+    #[test]
+    fn expands_multiple_providers_in_one_file_without_name_collisions() {
+        // Two providers declared side by side must each get their own impl module and struct type,
+        // derived from their own trait idents, so expanding both in the same crate doesn't produce
+        // a duplicate-definition error.
+        let file = data::multi_trait_file_with_call_sites();
+        let item_traits = extract_item_traits(&file);
+        assert_eq!(2, item_traits.len());
+        assert_ne!(
+            get_provider_impl_mod_name(&item_traits[0].ident),
+            get_provider_impl_mod_name(&item_traits[1].ident)
+        );
+        assert_ne!(
+            get_provider_impl_struct_type_name(&item_traits[0].ident),
+            get_provider_impl_struct_type_name(&item_traits[1].ident)
+        );
+
+        for item_trait in item_traits {
+            assert_eq!(
+                true,
+                prober_impl(item_trait).is_ok(),
+                "every provider trait in the fixture file should expand successfully on its own"
+            );
+        }
+    }
+
+    #[test]
+    fn expands_probe_call_sites_against_providers_declared_elsewhere_in_the_file() {
+        // `probe!` call sites, including the two-argument guarded form and a probe with several
+        // reference-typed arguments, must each parse as a `ProbeCallSpecification` and expand
+        // cleanly.
+        let invocations = extract_probe_invocations(&data::multi_trait_file_with_call_sites());
+        assert_eq!(3, invocations.len());
+
+        for tokens in invocations {
+            let call_spec: ProbeCallSpecification = syn::parse2(tokens)
+                .expect("every probe! call site in the fixture file should itself parse");
+            assert_eq!(true, probe_impl(call_spec).is_ok());
+        }
+    }
+
+    #[test]
+    fn probe_call_site_grammar_rejects_invalid_shapes() {
+        assert_eq!(
+            true,
+            syn::parse2::<ProbeCallSpecification>(data::invalid_call_site_empty()).is_err()
+        );
+        assert_eq!(
+            true,
+            syn::parse2::<ProbeCallSpecification>(data::invalid_call_site_not_a_call()).is_err()
+        );
+        assert_eq!(
+            true,
+            syn::parse2::<ProbeCallSpecification>(data::invalid_call_site_method_call()).is_err()
+        );
+        assert_eq!(
+            true,
+            syn::parse2::<ProbeCallSpecification>(data::invalid_call_site_too_many_args()).is_err()
+        );
+    }
 }
\ No newline at end of file
@@ -10,6 +10,34 @@ use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
 
+/// The severity level a probe was declared with, mirroring `tracers_core::filter::Level`.  Kept as
+/// its own type here, rather than using the runtime crate's enum directly, so the codegen crate
+/// doesn't need a dependency on `tracers-core` just to carry this one piece of parsed-attribute
+/// data around; `ToTokens` below is what ties the two back together when the value is spliced into
+/// generated code.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl quote::ToTokens for Level {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let variant = match self {
+            Level::Error => quote! { Error },
+            Level::Warn => quote! { Warn },
+            Level::Info => quote! { Info },
+            Level::Debug => quote! { Debug },
+            Level::Trace => quote! { Trace },
+        };
+
+        tokens.extend(quote! { ::tracers_core::filter::Level::#variant });
+    }
+}
+
 /// Base trait for the provider generators.  Contains logic that is common to all of the
 /// generators
 pub(super) trait ProviderTraitGeneratorBase {
@@ -39,7 +67,9 @@ This trait corresponds to a SystemTap/USDT provider named `{provider_name}`,
 
 ## Other platforms
 
-TODO: No other platforms supported yet
+Anywhere a native SystemTap/USDT provider isn't available, this falls back to a `tracing`-backed
+implementation instead: each probe becomes a `tracing::event!`, so attaching a `tracing_subscriber`
+(or any other `Subscriber`) to the process is enough to observe it.
 "###,
             provider_name = self.spec().name()
         );
@@ -119,6 +149,20 @@ TODO: No other platforms supported yet
         }
     }
 
+    /// Generates the expression that builds this provider's `EnvFilter` once, by parsing the
+    /// environment variable named after the provider (e.g. a provider named `my_probes` is
+    /// controlled by `MY_PROBES_LOG`).  The generated `__try_init_provider` stores the result in a
+    /// `OnceCell` alongside the provider instance itself, so every probe's is-enabled check is just
+    /// a cheap read of an already-parsed filter.
+    fn generate_filter_init_expr(&self) -> TokenStream {
+        let env_var_name = format!("{}_LOG", self.spec().name()).to_uppercase();
+
+        quote! {
+            ::tracers_core::filter::EnvFilter::from_env(#env_var_name)
+                .unwrap_or_else(|_| ::tracers_core::filter::EnvFilter::default())
+        }
+    }
+
     /// Returns the name of the module in which most of the implementation code for this trait will be
     /// located.
     fn get_provider_impl_mod_name(&self) -> syn::Ident {
@@ -136,6 +180,49 @@ TODO: No other platforms supported yet
     fn get_provider_impl_struct_type_name(&self) -> syn::Ident {
         crate::syn_helpers::add_suffix_to_ident(&self.spec().item_trait().ident, "ProviderImpl")
     }
+
+    /// Generates the declaration and a stub implementation of `__try_init_provider`, for use only
+    /// in the dummy provider produced when codegen has failed.  The stub always reports failure,
+    /// since if we've gotten this far the real provider could not be built.
+    fn generate_dummy_try_init_impl(&self) -> TokenStream {
+        let try_init_decl = self.generate_try_init_decl();
+
+        quote! {
+            #try_init_decl {
+                Err("this provider failed to compile; see the other compile errors reported alongside this one")
+            }
+        }
+    }
+
+    /// Generates a syntactically-complete but functionally inert stand-in for the provider
+    /// trait/struct, for use when codegen hits an error partway through and must abort.
+    ///
+    /// Without this, a single bad probe signature would cause the whole provider trait/struct to
+    /// go undefined, which means every other place that references it -- `probe!`, `init_provider!`,
+    /// calls to the trait's own methods -- would *also* fail to resolve, burying the one real error
+    /// under a flood of spurious "cannot find type/function" noise.  Emitting this dummy alongside
+    /// the accumulated `compile_error!`s means name resolution still succeeds, so the compiler only
+    /// shows the user the genuine, well-spanned diagnostics.  This is the same "dummy emission"
+    /// trick used by `proc-macro-error`'s `set_dummy`.
+    fn generate_dummy_provider<P: ProbeGeneratorBase>(&self, probes: &[P]) -> TokenStream {
+        let vis = &self.spec().item_trait().vis;
+        let ident = &self.spec().item_trait().ident;
+        let dummy_try_init = self.generate_dummy_try_init_impl();
+        let dummy_probes = probes
+            .iter()
+            .map(|probe| probe.generate_dummy_probe_method(self.spec()));
+
+        quote! {
+            #vis struct #ident;
+
+            #[allow(dead_code, unused_variables)]
+            impl #ident {
+                #dummy_try_init
+
+                #(#dummy_probes)*
+            }
+        }
+    }
 }
 
 /// Base trait for the provider generators.  Contains logic that is common to all of the
@@ -143,6 +230,32 @@ TODO: No other platforms supported yet
 pub(super) trait ProbeGeneratorBase {
     fn spec(&self) -> &ProbeSpecification;
 
+    /// The severity level this probe was declared with, e.g. via `#[level(debug)]` on the probe
+    /// method.  Probes default to `Level::Trace`, the least severe level, so a probe with no
+    /// explicit level annotation is enabled only by a matching directive at `trace` level.
+    fn level(&self) -> Level {
+        self.spec().level.unwrap_or(Level::Trace)
+    }
+
+    /// Generates the runtime check that consults the provider's parsed `EnvFilter` to decide
+    /// whether this probe is currently enabled, given the path segments (provider name, probe name)
+    /// that identify it in filter directives.  The `tracing` backend evaluates this at the top of
+    /// each generated probe method, so a disabled probe doesn't fire; the probe's argument
+    /// expressions have already been evaluated by the caller by then.
+    fn generate_filter_check(
+        &self,
+        provider: &ProviderSpecification,
+        filter_var: &syn::Ident,
+    ) -> TokenStream {
+        let provider_name = provider.name();
+        let probe_name = &self.spec().name;
+        let level = self.level();
+
+        quote! {
+            #filter_var.enabled(&[#provider_name, #probe_name], #level)
+        }
+    }
+
     /// Generates the `#[deprecated...]` attribute which triggers a warning if anyone tries to call the
     /// probe method directly, not through the `probe!` attribute
     fn generate_probe_deprecation_attribute(
@@ -193,7 +306,9 @@ where `${{PID}}` should be the actual process ID of the process you are tracing.
 
 ## Other platforms
 
-TODO: No other platforms supported yet
+Anywhere a native SystemTap/USDT provider isn't available, this probe is instead fired as a
+`tracing::event!`, observable by attaching a `tracing_subscriber` (or any other `Subscriber`) to
+the process.
 
 "###,
         trait_name = &provider.item_trait().ident,
@@ -203,6 +318,60 @@ TODO: No other platforms supported yet
 
         generate_multiline_comments(&probe_comment)
     }
+
+    /// Generates a stub version of this probe's method, for inclusion in the dummy provider that's
+    /// emitted when codegen fails.  The body is empty; all that matters is that the method exists
+    /// with the right name so that any reference to it still resolves.
+    fn generate_dummy_probe_method(&self, provider: &ProviderSpecification) -> TokenStream {
+        let deprecation_attr = self.generate_probe_deprecation_attribute(provider);
+        let method_name = &self.spec().method_name;
+        let span = method_name.span();
+
+        quote_spanned! {span=>
+            #deprecation_attr
+            #[allow(dead_code, unused_variables)]
+            pub fn #method_name() {}
+        }
+    }
+}
+
+/// Accumulates `syn::Error`s produced while generating a single provider, so that a bad probe
+/// doesn't abort codegen the moment it's found.  Once generation is done, `into_compile_error`
+/// combines everything collected into one `TokenStream` of `compile_error!` invocations -- one per
+/// diagnostic, each still pointing at its own span -- which the caller emits alongside the dummy
+/// provider produced by `generate_dummy_provider`.
+#[derive(Default)]
+pub(super) struct Diagnostics {
+    errors: Vec<syn::Error>,
+}
+
+impl Diagnostics {
+    pub(super) fn new() -> Self {
+        Diagnostics { errors: Vec::new() }
+    }
+
+    /// Records an error but does not stop generation; the caller should keep going and collect as
+    /// many diagnostics as it can before giving up.
+    pub(super) fn push<T: quote::ToTokens, U: std::fmt::Display>(&mut self, tokens: T, message: U) {
+        self.errors.push(syn::Error::new_spanned(tokens, message));
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Combines every collected error into a single `TokenStream`.  Each error keeps its own span,
+    /// so the user still gets one clearly-located message per problem instead of a single vague one.
+    pub(super) fn into_compile_error(self) -> Option<TokenStream> {
+        let mut iter = self.errors.into_iter();
+        let first = iter.next()?;
+        let combined = iter.fold(first, |mut combined, next| {
+            combined.combine(next);
+            combined
+        });
+
+        Some(combined.to_compile_error())
+    }
 }
 
 /// Generates the standard provider init call.  Some implementations may use a different one but
@@ -0,0 +1,216 @@
+//! A code generation backend that lowers probes onto the `tracing` crate instead of a native USDT
+//! provider.  Unlike the USDT backends this one has no platform dependency at all -- it works
+//! anywhere `tracing` does, including Windows, macOS, and inside `cargo test` -- so it serves as the
+//! portable fallback mentioned in the other generators' doc comments under "Other platforms".
+//!
+//! Each probe becomes a `tracing::event!` whose target is the provider name, with the probe name
+//! recorded in a field called `name`; each probe argument is recorded as a further field on that
+//! event.  An argument whose declared type is named as one of `tracing`'s native value types (an
+//! integer, float, `bool`, `str` or `String`, or a reference to one) is recorded directly; any
+//! other type is recorded via its `Debug` impl (`?field` syntax).  The choice is made from the
+//! type's name as written, not from its `ProbeArgType` impl.  Consumers attach in the usual way, with a `tracing_subscriber::fmt` subscriber or any
+//! other `Subscriber` implementation.
+//!
+//! Each probe still respects the provider's `EnvFilter` the same way the USDT backends do: a
+//! `OnceCell` holds the filter parsed from the provider's environment variable, and every probe
+//! method checks it before firing, so a disabled probe skips the `tracing::event!` call and the
+//! recording of its fields.  The argument expressions themselves are evaluated by the caller before
+//! the method runs.
+use super::common::{ProbeGeneratorBase, ProviderTraitGeneratorBase};
+use crate::build_rs::BuildInfo;
+use crate::spec::{ProbeSpecification, ProviderSpecification};
+use crate::TracersResult;
+use heck::ShoutySnakeCase;
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+/// Generates a `tracing`-backed implementation of a single provider trait.
+pub(crate) struct TracingProviderTraitGenerator<'a> {
+    spec: &'a ProviderSpecification,
+    build_info: &'a BuildInfo,
+}
+
+impl<'a> TracingProviderTraitGenerator<'a> {
+    pub(crate) fn new(
+        spec: &'a ProviderSpecification,
+        build_info: &'a BuildInfo,
+    ) -> TracingProviderTraitGenerator<'a> {
+        TracingProviderTraitGenerator { spec, build_info }
+    }
+
+    /// Generates the whole trait/struct for this provider, with every probe lowered to a
+    /// `tracing::event!` call.  There's no native provider to register and nothing can fail to
+    /// initialize, so `__try_init_provider` never returns `Err` -- but it still has real work to do:
+    /// parsing this provider's `EnvFilter` from its environment variable once, into a `OnceCell`
+    /// that every probe's is-enabled check reads cheaply, exactly as the doc comment on
+    /// `generate_filter_init_expr` promises.
+    pub(crate) fn generate(&self) -> TracersResult<TokenStream> {
+        let vis = &self.spec.item_trait().vis;
+        let ident = &self.spec.item_trait().ident;
+        let trait_comment = self.generate_trait_comment();
+        let try_init_decl = self.generate_try_init_decl();
+        let filter_static_name = self.get_filter_static_name();
+        let filter_init_expr = self.generate_filter_init_expr();
+
+        let probe_methods: Vec<TokenStream> = self
+            .spec
+            .probes()
+            .iter()
+            .map(|probe| {
+                TracingProbeGenerator::new(probe).generate(
+                    self.spec,
+                    &filter_static_name,
+                    &filter_init_expr,
+                )
+            })
+            .collect();
+
+        Ok(quote_spanned! { self.spec.item_trait().span() =>
+            #trait_comment
+            #vis struct #ident;
+
+            #[doc(hidden)]
+            static #filter_static_name: ::once_cell::sync::OnceCell<::tracers_core::filter::EnvFilter> =
+                ::once_cell::sync::OnceCell::new();
+
+            impl #ident {
+                #try_init_decl {
+                    #filter_static_name.get_or_init(|| #filter_init_expr);
+                    Ok("tracing")
+                }
+
+                #(#probe_methods)*
+            }
+        })
+    }
+
+    /// The name of the hidden `OnceCell` static that holds this provider's parsed `EnvFilter`,
+    /// e.g. `MY_PROBES_FILTER`.
+    fn get_filter_static_name(&self) -> syn::Ident {
+        let shouty_name = format!("{}Filter", self.spec.item_trait().ident).to_shouty_snake_case();
+
+        syn::Ident::new(&shouty_name, self.spec.item_trait().ident.span())
+    }
+}
+
+impl<'a> ProviderTraitGeneratorBase for TracingProviderTraitGenerator<'a> {
+    fn spec(&self) -> &ProviderSpecification {
+        self.spec
+    }
+
+    fn build_info(&self) -> &BuildInfo {
+        self.build_info
+    }
+}
+
+/// Generates the `tracing::event!` expansion for a single probe.
+struct TracingProbeGenerator<'a> {
+    spec: &'a ProbeSpecification,
+}
+
+impl<'a> TracingProbeGenerator<'a> {
+    fn new(spec: &'a ProbeSpecification) -> TracingProbeGenerator<'a> {
+        TracingProbeGenerator { spec }
+    }
+
+    fn generate(
+        &self,
+        provider: &ProviderSpecification,
+        filter_static_name: &syn::Ident,
+        filter_init_expr: &TokenStream,
+    ) -> TokenStream {
+        let doc_comment = self.generate_probe_doc_comment(provider);
+        let deprecation_attr = self.generate_probe_deprecation_attribute(provider);
+        let method_name = &self.spec.method_name;
+        let probe_name = &self.spec.name;
+        let provider_name = provider.name();
+        let span = method_name.span();
+
+        let filter_var = syn::Ident::new("__tracers_filter", span);
+        let filter_check = self.generate_filter_check(provider, &filter_var);
+
+        let params: Vec<TokenStream> = self
+            .spec
+            .args
+            .iter()
+            .map(|arg| {
+                let name = &arg.name;
+                let ty = &arg.ty;
+                quote_spanned! {name.span()=> #name: #ty }
+            })
+            .collect();
+
+        // A type `tracing`'s `Value` trait understands natively (an integer, float, `bool`, or
+        // string) is recorded directly; anything else falls back to being recorded via its
+        // `Debug` impl (the `?field` syntax), the same fallback `FuncProbeArgTypeWrapper` uses,
+        // so this backend never has to reject an argument type the USDT backends accept.
+        let fields: Vec<TokenStream> = self
+            .spec
+            .args
+            .iter()
+            .map(|arg| {
+                let name = &arg.name;
+                if is_tracing_native_value_type(&arg.ty) {
+                    quote_spanned! {name.span()=> #name = #name }
+                } else {
+                    quote_spanned! {name.span()=> #name = ?#name }
+                }
+            })
+            .collect();
+
+        quote_spanned! {span=>
+            #doc_comment
+            #deprecation_attr
+            #[allow(dead_code, unused_variables)]
+            pub fn #method_name(#(#params),*) {
+                let #filter_var = #filter_static_name.get_or_init(|| #filter_init_expr);
+                if #filter_check {
+                    ::tracing::event!(
+                        target: #provider_name,
+                        ::tracing::Level::TRACE,
+                        name = #probe_name,
+                        #(#fields),*
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Whether `ty` is one of the handful of types `tracing`'s `Value` trait records natively --
+/// everything else is recorded via its `Debug` impl instead (see `generate` above).
+fn is_tracing_native_value_type(ty: &syn::Type) -> bool {
+    let path = match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => &type_path.path,
+        syn::Type::Reference(type_ref) => return is_tracing_native_value_type(&type_ref.elem),
+        _ => return false,
+    };
+
+    path.segments.last().map_or(false, |segment| {
+        matches!(
+            segment.ident.to_string().as_str(),
+            "bool"
+                | "str"
+                | "String"
+                | "i8"
+                | "i16"
+                | "i32"
+                | "i64"
+                | "isize"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "usize"
+                | "f32"
+                | "f64"
+        )
+    })
+}
+
+impl<'a> ProbeGeneratorBase for TracingProbeGenerator<'a> {
+    fn spec(&self) -> &ProbeSpecification {
+        self.spec
+    }
+}
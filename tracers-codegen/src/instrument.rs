@@ -0,0 +1,179 @@
+//! Implements the `#[instrument]` attribute, which wraps an ordinary function so that entering it
+//! fires a generated `<fn>_enter` probe capturing the function's arguments, and leaving it -- by
+//! normal return, early `return`, or unwinding panic -- fires a `<fn>_exit` probe, optionally
+//! capturing the return value.  This is modeled closely on `tracing-attributes`' `#[instrument]`:
+//! the probes synthesized here are ordinary `tracers` probes, generated via a hidden `#[tracer]`
+//! trait instead of one the user writes by hand, so they show up to `bpftrace`/`tplist` exactly the
+//! same way a hand-declared provider would.
+use crate::syn_helpers;
+use crate::TracersResult;
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{FnArg, Ident, ItemFn, Pat};
+
+/// Parameters accepted by `#[instrument(...)]` to customize the generated probes.
+#[derive(Default)]
+pub struct InstrumentArgs {
+    /// Overrides the generated enter/exit probe names; defaults to `<fn>_enter`/`<fn>_exit`.
+    pub enter_name: Option<String>,
+    pub exit_name: Option<String>,
+    /// Parameter names to omit from the captured `_enter` probe, e.g. because their type isn't
+    /// `Copy` or a reference and so can't be a probe argument without further wrapping.
+    pub skip: Vec<String>,
+    /// Whether to capture the function's return value on the `_exit` probe.  This only covers the
+    /// function's normal (tail-expression) exit path; a function that exits via an early `return`
+    /// still fires `_exit`, but without the captured value, since by the time the guard's `Drop`
+    /// runs there is no return value in scope to read.
+    pub capture_return: bool,
+}
+
+/// Rewrites `item` to fire a `<fn>_enter` probe as the first statement of the function body, and a
+/// `<fn>_exit` probe when the function is left by any path.
+///
+/// The exit probe is fired from the `Drop` impl of a guard value created immediately after
+/// `_enter`, whose scope spans the rest of the function body.  This covers early `return`s and
+/// unwinding panics without the caller having to instrument every exit point by hand.  For `async
+/// fn`, the same guard is simply captured by the generated future; it fires on whatever drops the
+/// future, whether that's normal completion or the future being cancelled before it completes.
+pub fn instrument_impl(args: InstrumentArgs, item: ItemFn) -> TracersResult<TokenStream> {
+    let vis = &item.vis;
+    let sig = &item.sig;
+    let fn_name = &sig.ident;
+    let block = &item.block;
+    let attrs = &item.attrs;
+    let span = item.span();
+
+    let enter_probe_name = args
+        .enter_name
+        .clone()
+        .unwrap_or_else(|| format!("{}_enter", fn_name));
+    let exit_probe_name = args
+        .exit_name
+        .clone()
+        .unwrap_or_else(|| format!("{}_exit", fn_name));
+
+    //Every typed, non-`self` parameter is captured on the `_enter` probe unless the caller asked to
+    //skip it by name.  Only a plain identifier pattern works here: `captured_args` becomes the
+    //declared parameter list of a trait method with no body, where a destructured pattern like
+    //`(a, b): (i32, i32)` isn't legal syntax, and `arg_idents` needs a single in-scope name to pass
+    //to `probe!` at the call site anyway.
+    let mut captured_args: Vec<&FnArg> = Vec::new();
+    for arg in sig.inputs.iter() {
+        match arg {
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat_ident) => {
+                    if !args.skip.contains(&pat_ident.ident.to_string()) {
+                        captured_args.push(arg);
+                    }
+                }
+                other => {
+                    return Err(crate::TracersError::new(
+                        "#[instrument] can't capture a parameter bound by a pattern other than a \
+                         plain identifier; add it to `skip(...)` instead",
+                        other.span(),
+                    ));
+                }
+            },
+            FnArg::Receiver(_) => {}
+        }
+    }
+
+    let arg_idents: Vec<&Ident> = captured_args
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat_ident) => Some(&pat_ident.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    //Name the hidden provider after the function it instruments, following the same naming
+    //convention `#[tracer]` itself uses for its generated impl module and struct.
+    let provider_ident = syn_helpers::add_suffix_to_ident(fn_name, "InstrumentProbes");
+    let enter_ident = Ident::new(&enter_probe_name, fn_name.span());
+    let exit_ident = Ident::new(&exit_probe_name, fn_name.span());
+
+    let guard_type = Ident::new("__TracersInstrumentGuard", span);
+
+    let result = if args.capture_return {
+        let return_ty = match &sig.output {
+            syn::ReturnType::Type(_, ty) => (**ty).clone(),
+            syn::ReturnType::Default => syn::parse_quote! { () },
+        };
+
+        //The exit probe takes `Option<&ReturnTy>` rather than `&ReturnTy` so that the guard's
+        //fallback firing (panic or early `return`, where no result is available) can pass `None`
+        //instead of needing a `Default` impl on an arbitrary return type.
+        let provider_trait = quote_spanned! {span=>
+            #[::tracers_macros::tracer]
+            trait #provider_ident {
+                fn #enter_ident(#(#captured_args),*);
+                fn #exit_ident(result: Option<&#return_ty>);
+            }
+        };
+
+        quote_spanned! {span=>
+            #provider_trait
+
+            #(#attrs)*
+            #vis #sig {
+                ::tracers_macros::probe!(#provider_ident::#enter_ident(#(#arg_idents),*));
+
+                //This guard only covers exit via panic or early `return`: the normal tail-expression
+                //exit path below fires its own, result-carrying, exit probe and marks the guard as
+                //already having fired so `Drop` doesn't duplicate it.
+                struct #guard_type {
+                    fired: ::core::cell::Cell<bool>,
+                }
+                impl ::core::ops::Drop for #guard_type {
+                    fn drop(&mut self) {
+                        if !self.fired.get() {
+                            ::tracers_macros::probe!(#provider_ident::#exit_ident(None));
+                        }
+                    }
+                }
+                let __tracers_instrument_guard = #guard_type {
+                    fired: ::core::cell::Cell::new(false),
+                };
+
+                let __tracers_instrument_result = #block;
+
+                __tracers_instrument_guard.fired.set(true);
+                ::tracers_macros::probe!(#provider_ident::#exit_ident(Some(&__tracers_instrument_result)));
+                __tracers_instrument_result
+            }
+        }
+    } else {
+        let provider_trait = quote_spanned! {span=>
+            #[::tracers_macros::tracer]
+            trait #provider_ident {
+                fn #enter_ident(#(#captured_args),*);
+                fn #exit_ident();
+            }
+        };
+
+        quote_spanned! {span=>
+            #provider_trait
+
+            #(#attrs)*
+            #vis #sig {
+                ::tracers_macros::probe!(#provider_ident::#enter_ident(#(#arg_idents),*));
+
+                struct #guard_type;
+                impl ::core::ops::Drop for #guard_type {
+                    fn drop(&mut self) {
+                        ::tracers_macros::probe!(#provider_ident::#exit_ident());
+                    }
+                }
+                let __tracers_instrument_guard = #guard_type;
+
+                #block
+            }
+        }
+    };
+
+    Ok(result)
+}
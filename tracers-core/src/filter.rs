@@ -0,0 +1,161 @@
+//! A small directive-based filter, modeled on `tracing-subscriber`'s `EnvFilter`, that lets a
+//! provider's probes be selectively enabled or disabled at runtime via an environment variable.
+//!
+//! The grammar is a comma-separated list of directives of the form `target=level` or a bare
+//! `level` which sets the default for any target not otherwise matched, e.g.:
+//!
+//! ```text
+//! MYPROV_LOG=info,MyProbes::detail=trace
+//! ```
+//!
+//! `target` may be a provider name, a `provider::probe` path, or a `*` wildcard segment.  When more
+//! than one directive could match a given target, the most specific one wins: an exact
+//! `provider::probe` directive beats a bare `provider` directive, which beats a wildcard, which
+//! beats the default level.
+use std::str::FromStr;
+
+/// The severity of a single probe, or of a directive that matches one or more probes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = ParseFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(ParseFilterError::new(format!("'{}' is not a valid level (expected one of error, warn, info, debug, trace)", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFilterError {
+    message: String,
+}
+
+impl ParseFilterError {
+    fn new<M: Into<String>>(message: M) -> ParseFilterError {
+        ParseFilterError {
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseFilterError {}
+
+/// A single parsed directive, e.g. `MyProbes::detail=trace` or a bare `info`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Directive {
+    /// The path segments of the target this directive matches, e.g. `["MyProbes", "detail"]`, or
+    /// empty for a bare default-level directive.  A `*` segment matches anything at that position.
+    target: Vec<String>,
+    level: Level,
+}
+
+impl Directive {
+    /// How many non-wildcard path segments this directive specifies.  Used to rank directives by
+    /// specificity: more concrete segments means a better match.
+    fn specificity(&self) -> usize {
+        self.target.iter().filter(|s| s.as_str() != "*").count()
+    }
+
+    /// Returns `true` if this directive's target matches the given `target` path.
+    fn matches(&self, target: &[&str]) -> bool {
+        if self.target.is_empty() {
+            // A bare directive with no target matches everything; it's the default level.
+            return true;
+        }
+        if self.target.len() > target.len() {
+            return false;
+        }
+        self.target
+            .iter()
+            .zip(target.iter())
+            .all(|(pattern, segment)| pattern == "*" || pattern == segment)
+    }
+}
+
+/// A parsed filter, built once (typically at provider initialization) from an environment
+/// variable, then consulted cheaply every time a probe needs to know whether it's enabled.
+#[derive(Debug, Clone, Default)]
+pub struct EnvFilter {
+    directives: Vec<Directive>,
+}
+
+impl EnvFilter {
+    /// Parses a filter directive string of the form `target=level,target=level,...` or a bare
+    /// `level` as a default.  Directives are comma-separated; whitespace around each directive is
+    /// ignored.
+    pub fn parse(s: &str) -> Result<EnvFilter, ParseFilterError> {
+        let mut directives = Vec::new();
+
+        for part in s.split(',') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+
+            let directive = match part.find('=') {
+                Some(idx) => {
+                    let (target, level) = part.split_at(idx);
+                    let level = &level[1..]; // skip the '='
+                    Directive {
+                        target: target.split("::").map(str::to_string).collect(),
+                        level: level.parse()?,
+                    }
+                }
+                None => Directive {
+                    target: Vec::new(),
+                    level: part.parse()?,
+                },
+            };
+
+            directives.push(directive);
+        }
+
+        Ok(EnvFilter { directives })
+    }
+
+    /// Reads the named environment variable and parses it as a filter.  If the variable isn't set,
+    /// returns a filter that disables everything, which matches the crate's convention of probes
+    /// being silent unless explicitly enabled.
+    pub fn from_env(var_name: &str) -> Result<EnvFilter, ParseFilterError> {
+        match std::env::var(var_name) {
+            Ok(value) => EnvFilter::parse(&value),
+            Err(std::env::VarError::NotPresent) => Ok(EnvFilter::default()),
+            Err(std::env::VarError::NotUnicode(_)) => Err(ParseFilterError::new(format!(
+                "the value of {} is not valid unicode",
+                var_name
+            ))),
+        }
+    }
+
+    /// Determines whether a probe at the given target path (e.g. `["MyProbes", "detail"]`) and
+    /// declared `level` should fire, given the directives in this filter.  Of all the directives
+    /// that match this target, the most specific one wins; if none match, the probe is disabled.
+    pub fn enabled(&self, target: &[&str], level: Level) -> bool {
+        self.directives
+            .iter()
+            .filter(|d| d.matches(target))
+            .max_by_key(|d| (d.specificity(), !d.target.is_empty()))
+            .map(|d| level <= d.level)
+            .unwrap_or(false)
+    }
+}